@@ -0,0 +1,17 @@
+# Contact lookup approval (contacts.rs)
+contact-lookup-title = Kontaktsuche erlauben?
+contact-lookup-message = AIThing möchte „{ $name }“ in Ihren Kontakten nachschlagen.
+contact-lookup-allow = Erlauben
+contact-lookup-deny = Nicht erlauben
+contact-lookup-disabled = Die Kontaktsuche ist in den Einstellungen deaktiviert
+contact-lookup-declined = Die Kontaktsuche wurde abgelehnt
+
+# Factory reset confirmation (lifecycle.rs)
+factory-reset-title = AIThing zurücksetzen
+factory-reset-message = Dadurch werden alle Einstellungen, Profile, Erinnerungen, Chats und gespeicherten API-Schlüssel gelöscht und AIThing neu gestartet. Dies kann nicht rückgängig gemacht werden.
+factory-reset-confirm = Alles löschen
+factory-reset-cancel = Abbrechen
+
+# VoiceOver (voiceover.rs)
+panel-accessibility-title = AIThing
+response-finished-announcement = Antwort fertig.
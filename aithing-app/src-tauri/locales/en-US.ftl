@@ -0,0 +1,17 @@
+# Contact lookup approval (contacts.rs)
+contact-lookup-title = Allow Contact Lookup?
+contact-lookup-message = AIThing wants to look up "{ $name }" in your contacts.
+contact-lookup-allow = Allow
+contact-lookup-deny = Don't Allow
+contact-lookup-disabled = Contact lookup is disabled in settings
+contact-lookup-declined = Contact lookup was declined
+
+# Factory reset confirmation (lifecycle.rs)
+factory-reset-title = Reset AIThing
+factory-reset-message = This erases all settings, profiles, memories, chats, and saved API keys, then restarts AIThing. This can't be undone.
+factory-reset-confirm = Erase Everything
+factory-reset-cancel = Cancel
+
+# VoiceOver (voiceover.rs)
+panel-accessibility-title = AIThing
+response-finished-announcement = Response ready.
@@ -0,0 +1,139 @@
+//! Read-only Accessibility (AX) queries
+//!
+//! Thin wrapper over the AXUIElement API for reading state from other apps'
+//! windows. Every query returns `None` until the user grants Accessibility
+//! access in System Settings, so callers treat AX as a best-effort fallback.
+
+use core_foundation::base::{CFType, CFTypeRef, TCFType};
+use core_foundation::string::{CFString, CFStringRef};
+use std::ffi::c_void;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> u8;
+    fn AXUIElementCreateApplication(pid: i32) -> CFTypeRef;
+    fn AXUIElementCreateSystemWide() -> CFTypeRef;
+    fn AXUIElementCopyAttributeValue(
+        element: CFTypeRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXUIElementCopyParameterizedAttributeValue(
+        element: CFTypeRef,
+        attribute: CFStringRef,
+        parameter: CFTypeRef,
+        value: *mut CFTypeRef,
+    ) -> i32;
+    fn AXValueGetValue(value: CFTypeRef, value_type: u32, value_ptr: *mut c_void) -> u8;
+}
+
+const AX_ERROR_SUCCESS: i32 = 0;
+// AXValueType
+const AX_VALUE_CG_RECT: u32 = 3;
+
+/// Screen rectangle in points, origin at the top left of the main display.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(C)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub fn is_trusted() -> bool {
+    unsafe { AXIsProcessTrusted() != 0 }
+}
+
+fn copy_attribute(element: &CFType, attribute: &str) -> Option<CFType> {
+    let attribute = CFString::new(attribute);
+    let mut value: CFTypeRef = std::ptr::null();
+
+    unsafe {
+        let error = AXUIElementCopyAttributeValue(
+            element.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            &mut value,
+        );
+        if error != AX_ERROR_SUCCESS || value.is_null() {
+            None
+        } else {
+            Some(CFType::wrap_under_create_rule(value))
+        }
+    }
+}
+
+fn copy_parameterized_attribute(
+    element: &CFType,
+    attribute: &str,
+    parameter: &CFType,
+) -> Option<CFType> {
+    let attribute = CFString::new(attribute);
+    let mut value: CFTypeRef = std::ptr::null();
+
+    unsafe {
+        let error = AXUIElementCopyParameterizedAttributeValue(
+            element.as_CFTypeRef(),
+            attribute.as_concrete_TypeRef(),
+            parameter.as_CFTypeRef(),
+            &mut value,
+        );
+        if error != AX_ERROR_SUCCESS || value.is_null() {
+            None
+        } else {
+            Some(CFType::wrap_under_create_rule(value))
+        }
+    }
+}
+
+/// Screen bounds of the text caret (or selection) in whichever app has
+/// keyboard focus. Apps that don't expose text ranges through AX return
+/// `None`.
+pub fn focused_caret_bounds() -> Option<Rect> {
+    if !is_trusted() {
+        return None;
+    }
+
+    let system = unsafe {
+        let element = AXUIElementCreateSystemWide();
+        if element.is_null() {
+            return None;
+        }
+        CFType::wrap_under_create_rule(element)
+    };
+    let focused = copy_attribute(&system, "AXFocusedUIElement")?;
+    let range = copy_attribute(&focused, "AXSelectedTextRange")?;
+    let bounds = copy_parameterized_attribute(&focused, "AXBoundsForRange", &range)?;
+
+    let mut rect = Rect::default();
+    let ok = unsafe {
+        AXValueGetValue(
+            bounds.as_CFTypeRef(),
+            AX_VALUE_CG_RECT,
+            &mut rect as *mut Rect as *mut c_void,
+        )
+    };
+    // Some apps answer with an empty rect at the origin instead of failing
+    (ok != 0 && (rect.x != 0.0 || rect.y != 0.0)).then_some(rect)
+}
+
+/// Reads a string attribute (e.g. `AXTitle`, `AXDocument`) from the focused
+/// window of the process `pid`.
+pub fn focused_window_string(pid: i32, attribute: &str) -> Option<String> {
+    if !is_trusted() {
+        return None;
+    }
+
+    let application = unsafe {
+        let element = AXUIElementCreateApplication(pid);
+        if element.is_null() {
+            return None;
+        }
+        CFType::wrap_under_create_rule(element)
+    };
+
+    let window = copy_attribute(&application, "AXFocusedWindow")?;
+    copy_attribute(&window, attribute)?
+        .downcast::<CFString>()
+        .map(|value| value.to_string())
+}
@@ -0,0 +1,204 @@
+//! Activation latency
+//!
+//! Times each activation from the shortcut press to the panel being visible
+//! and then to the input being focused, and reports percentiles over recent
+//! activations. The budget for press-to-focused is 100 ms; slower activations
+//! are logged. The panel is created once at startup and only shown and
+//! hidden afterwards, so an activation is a reposition and an `orderFront`,
+//! never a window or webview load.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::Instant;
+use tauri::State;
+
+use crate::app_core::AppCore;
+
+/// Press-to-focused budget, in milliseconds.
+pub const ACTIVATION_BUDGET_MS: f64 = 100.0;
+/// Activations kept for the percentiles.
+const MAX_SAMPLES: usize = 200;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Milliseconds from the press, over the last `count` activations.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivationTimings {
+    pub count: usize,
+    /// Press to panel visible
+    pub visible: Percentiles,
+    /// Press to input focused, over activations where focus was reported
+    pub focused: Percentiles,
+    pub budget_ms: f64,
+    /// Activations that went over the budget
+    pub over_budget: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    visible_ms: f64,
+    focused_ms: Option<f64>,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct ActivationState {
+    /// When the current activation started, and when the panel became
+    /// visible, in ms from the press
+    pending: Mutex<Option<(Instant, Option<f64>)>>,
+    samples: Mutex<VecDeque<Sample>>,
+}
+
+impl ActivationState {
+    fn record(&self, sample: Sample) {
+        let total = sample.focused_ms.unwrap_or(sample.visible_ms);
+        if total > ACTIVATION_BUDGET_MS {
+            eprintln!(
+                "Activation took {:.1} ms, over the {} ms budget",
+                total, ACTIVATION_BUDGET_MS
+            );
+        }
+
+        let mut samples = self.samples.lock();
+        if samples.len() == MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+}
+
+// =============================================================================
+// TIMING
+// =============================================================================
+
+/// Starts timing an activation at the shortcut press. An activation still
+/// waiting for focus is recorded as it stands.
+pub fn begin(core: &AppCore) {
+    let previous = core
+        .activation
+        .pending
+        .lock()
+        .replace((Instant::now(), None));
+    if let Some((_, Some(visible_ms))) = previous {
+        core.activation.record(Sample {
+            visible_ms,
+            focused_ms: None,
+        });
+    }
+}
+
+/// Marks the panel visible for the current activation.
+pub fn mark_visible(core: &AppCore) {
+    if let Some((started, visible)) = core.activation.pending.lock().as_mut() {
+        visible.get_or_insert_with(|| elapsed_ms(*started));
+    }
+}
+
+/// Drops the current activation; it hid the panel rather than showing it.
+pub fn cancel(core: &AppCore) {
+    core.activation.pending.lock().take();
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile `p` (0-100) of sorted `values`.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn percentiles(mut values: Vec<f64>) -> Percentiles {
+    values.sort_by(f64::total_cmp);
+    Percentiles {
+        p50: percentile(&values, 50.0),
+        p90: percentile(&values, 90.0),
+        p99: percentile(&values, 99.0),
+        max: values.last().copied().unwrap_or(0.0),
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Called by the frontend once the input has focus after `panel-shown`;
+/// completes the current activation.
+#[tauri::command]
+pub fn mark_input_focused(core: State<'_, AppCore>) {
+    let Some((started, visible)) = core.activation.pending.lock().take() else {
+        return;
+    };
+    let focused_ms = elapsed_ms(started);
+    core.activation.record(Sample {
+        visible_ms: visible.unwrap_or(focused_ms),
+        focused_ms: Some(focused_ms),
+    });
+}
+
+#[tauri::command]
+pub fn get_activation_timings(core: State<'_, AppCore>) -> ActivationTimings {
+    let samples = core.activation.samples.lock();
+    ActivationTimings {
+        count: samples.len(),
+        visible: percentiles(samples.iter().map(|s| s.visible_ms).collect()),
+        focused: percentiles(samples.iter().filter_map(|s| s.focused_ms).collect()),
+        budget_ms: ACTIVATION_BUDGET_MS,
+        over_budget: samples
+            .iter()
+            .filter(|s| s.focused_ms.unwrap_or(s.visible_ms) > ACTIVATION_BUDGET_MS)
+            .count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::Manager;
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        let result = percentiles(values);
+
+        assert_eq!(result.p50, 50.0);
+        assert_eq!(result.p90, 90.0);
+        assert_eq!(result.p99, 99.0);
+        assert_eq!(result.max, 100.0);
+        assert_eq!(percentiles(Vec::new()), Percentiles::default());
+    }
+
+    #[test]
+    fn activations_are_recorded_once_focused() {
+        let app = crate::test_support::Harness::new().launch();
+        let core = app.state::<AppCore>();
+
+        begin(&core);
+        mark_visible(&core);
+        mark_input_focused(core.clone());
+        // Focus without an activation in progress is ignored
+        mark_input_focused(core.clone());
+
+        let timings = get_activation_timings(core);
+        assert_eq!(timings.count, 1);
+        assert!(timings.focused.max >= timings.visible.max);
+    }
+}
@@ -0,0 +1,127 @@
+//! Frontmost application tracking
+//!
+//! Identifies the app the user is working in (the panel never becomes
+//! frontmost itself) and checks it against the user's exclusion list, so
+//! AIThing stays out of password managers, banking apps, and the like.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::app_core::AppCore;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveApp {
+    pub bundle_id: Option<String>,
+    pub excluded: bool,
+}
+
+// =============================================================================
+// FRONTMOST APPLICATION
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+fn frontmost_application() -> Option<*mut objc2::runtime::AnyObject> {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+
+    unsafe {
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return None;
+        }
+        let app: *mut AnyObject = msg_send![workspace, frontmostApplication];
+        (!app.is_null()).then_some(app)
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn frontmost_bundle_id() -> Option<String> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+    use std::ffi::{c_char, CStr};
+
+    let app = frontmost_application()?;
+    unsafe {
+        let bundle_id: *mut AnyObject = msg_send![app, bundleIdentifier];
+        if bundle_id.is_null() {
+            return None;
+        }
+        let utf8: *const c_char = msg_send![bundle_id, UTF8String];
+        if utf8.is_null() {
+            return None;
+        }
+        Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+    }
+}
+
+/// Process ID of the frontmost app, for Accessibility queries against it.
+#[cfg(target_os = "macos")]
+pub fn frontmost_pid() -> Option<i32> {
+    use objc2::msg_send;
+
+    let app = frontmost_application()?;
+    let pid: i32 = unsafe { msg_send![app, processIdentifier] };
+    (pid > 0).then_some(pid)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn frontmost_bundle_id() -> Option<String> {
+    None
+}
+
+// =============================================================================
+// EXCLUSION LIST
+// =============================================================================
+
+fn is_excluded(core: &AppCore, bundle_id: Option<&str>) -> bool {
+    let Some(bundle_id) = bundle_id else {
+        return false;
+    };
+    core.settings
+        .read()
+        .excluded_apps
+        .iter()
+        .any(|excluded| excluded.eq_ignore_ascii_case(bundle_id))
+}
+
+/// True when the frontmost app is on the exclusion list. Shortcuts are
+/// suppressed and no context may be captured while this holds.
+pub fn is_frontmost_app_excluded(core: &AppCore) -> bool {
+    is_excluded(core, frontmost_bundle_id().as_deref())
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns the frontmost app and whether it is excluded. The frontend checks
+/// this before accepting dropped files; the capture commands check the
+/// exclusion themselves.
+#[tauri::command]
+pub fn get_active_app(core: State<'_, AppCore>) -> ActiveApp {
+    let bundle_id = frontmost_bundle_id();
+    let excluded = is_excluded(&core, bundle_id.as_deref());
+    ActiveApp {
+        bundle_id,
+        excluded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exclusion_ignores_bundle_id_case() {
+        let core = AppCore::default();
+        core.settings.write().excluded_apps = vec!["com.bitwarden.desktop".to_string()];
+
+        assert!(is_excluded(&core, Some("com.Bitwarden.Desktop")));
+        assert!(!is_excluded(&core, Some("com.apple.Safari")));
+        assert!(!is_excluded(&core, None));
+    }
+}
@@ -0,0 +1,43 @@
+//! Backend state
+//!
+//! Everything the backend mutates at runtime lives in one `AppCore`,
+//! registered with `app.manage()` at startup and injected into commands as
+//! `State<'_, AppCore>`. Each module defines the state type for its own slice
+//! and keeps its fields private; only settings and the panel's window state,
+//! which many modules read, are held directly.
+//!
+//! Locks are parking_lot's, which don't poison, so a panic in one command
+//! can't wedge state for the rest of the session.
+
+use parking_lot::RwLock;
+
+use crate::{
+    activation, calc, dotfile, event_tap, layout, lifecycle, memory, mock_provider, policy,
+    profiles, providers, quick_look, runtime, shortcuts, sleep_prevention, suspend, theme, vcr,
+    windows, AppSettings, WindowState,
+};
+
+#[derive(Default)]
+pub struct AppCore {
+    pub settings: RwLock<AppSettings>,
+    pub window: RwLock<WindowState>,
+    pub policy: policy::PolicyState,
+    pub activation: activation::ActivationState,
+    pub calc: calc::CalcState,
+    pub dotfile: dotfile::DotfileState,
+    pub event_tap: event_tap::EventTapState,
+    pub shortcuts: shortcuts::ShortcutsState,
+    pub suspend: suspend::SuspendState,
+    pub theme: theme::ThemeState,
+    pub layout: layout::LayoutState,
+    pub windows: windows::WindowRegistry,
+    pub memory: memory::MemoryState,
+    pub profiles: profiles::ProfileState,
+    pub providers: providers::ProviderState,
+    pub mock: mock_provider::MockState,
+    pub vcr: vcr::VcrState,
+    pub quick_look: quick_look::PreviewState,
+    pub runtime: runtime::RuntimeState,
+    pub lifecycle: lifecycle::LifecycleState,
+    pub sleep_prevention: sleep_prevention::SleepPreventionState,
+}
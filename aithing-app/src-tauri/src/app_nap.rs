@@ -0,0 +1,87 @@
+//! App Nap activity assertions
+//!
+//! macOS naps apps whose windows are hidden or occluded, which throttles
+//! their timers and network reads; a response streaming into the hidden
+//! panel would stall until the user brought it back. Work the user is
+//! waiting on holds an `Activity` for as long as it runs, and dropping it
+//! lets the app nap again. The assertion allows idle system sleep, so
+//! holding one doesn't keep the Mac awake.
+
+#[cfg(target_os = "macos")]
+use objc2::rc::Retained;
+#[cfg(target_os = "macos")]
+use objc2::runtime::AnyObject;
+
+/// NSActivityUserInitiatedAllowingIdleSystemSleep
+#[cfg(target_os = "macos")]
+const USER_INITIATED_ALLOWING_IDLE_SLEEP: u64 = 0x00EF_FFFF;
+
+/// Held while App Nap should leave the app alone; ends the activity on drop.
+pub struct Activity {
+    #[cfg(target_os = "macos")]
+    token: Option<Retained<AnyObject>>,
+}
+
+// Activity tokens can be ended from any thread
+unsafe impl Send for Activity {}
+unsafe impl Sync for Activity {}
+
+/// Starts an activity described by `reason`, which shows up in tools like
+/// Activity Monitor's energy tab.
+#[cfg(target_os = "macos")]
+pub fn begin(reason: &str) -> Activity {
+    use objc2::rc::autoreleasepool;
+    use objc2::{class, msg_send};
+    use std::ffi::CString;
+
+    let Ok(reason) = CString::new(reason) else {
+        return Activity { token: None };
+    };
+    // Generations begin on async runtime threads, which have no autorelease
+    // pool; drain the reason string and the token's autorelease here
+    let token = autoreleasepool(|_| unsafe {
+        let process_info: *mut AnyObject = msg_send![class!(NSProcessInfo), processInfo];
+        let reason: *mut AnyObject =
+            msg_send![class!(NSString), stringWithUTF8String: reason.as_ptr()];
+        if process_info.is_null() || reason.is_null() {
+            None
+        } else {
+            let token: *mut AnyObject = msg_send![
+                process_info,
+                beginActivityWithOptions: USER_INITIATED_ALLOWING_IDLE_SLEEP,
+                reason: reason
+            ];
+            // Autoreleased; keep it until the activity ends
+            Retained::retain(token)
+        }
+    });
+    if token.is_none() {
+        eprintln!("Failed to begin App Nap activity");
+    }
+    Activity { token }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn begin(_reason: &str) -> Activity {
+    Activity {}
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for Activity {
+    fn drop(&mut self) {
+        use objc2::rc::autoreleasepool;
+        use objc2::{class, msg_send};
+
+        let Some(token) = self.token.take() else {
+            return;
+        };
+        autoreleasepool(|_| unsafe {
+            let process_info: *mut AnyObject = msg_send![class!(NSProcessInfo), processInfo];
+            if !process_info.is_null() {
+                let _: () = msg_send![process_info, endActivity: &*token];
+            }
+            // Release the token inside the pool too
+            drop(token);
+        });
+    }
+}
@@ -0,0 +1,282 @@
+//! Native window appearance
+//!
+//! Draws a system material (NSVisualEffectView on macOS, Mica/Acrylic on
+//! Windows) behind the transparent webview so the panel gets the same frosted
+//! glass look as system panels, and tracks the system light/dark appearance
+//! and accent color so the UI and native materials stay in sync. The material
+//! is dropped while Reduce Transparency is on, and Reduce Motion, Increase
+//! Contrast, and the preferred text size are reported so the UI can follow.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::window::{Effect, EffectState, EffectsBuilder};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::app_core::AppCore;
+use crate::{runtime, theme};
+
+/// How often the system appearance is re-checked. Reading user defaults is
+/// cheap, and a poll avoids depending on distributed notification observers.
+const APPEARANCE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppearanceMode {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SystemAppearance {
+    pub mode: AppearanceMode,
+    /// Accent color as a hex string, e.g. `#007AFF`.
+    pub accent_color: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AccessibilityPrefs {
+    pub reduce_motion: bool,
+    pub reduce_transparency: bool,
+    pub increase_contrast: bool,
+    /// Preferred body text size relative to the 13pt default; macOS has no
+    /// content size categories, so this is the closest equivalent
+    pub text_scale: f64,
+}
+
+impl Default for AccessibilityPrefs {
+    fn default() -> Self {
+        Self {
+            reduce_motion: false,
+            reduce_transparency: false,
+            increase_contrast: false,
+            text_scale: 1.0,
+        }
+    }
+}
+
+// =============================================================================
+// NATIVE MATERIALS
+// =============================================================================
+
+/// Attaches `material` behind the main window's webview with rounded corners,
+/// or removes the current material when `None` or Reduce Transparency is on.
+pub fn apply_material(
+    app: &AppHandle,
+    material: Option<Effect>,
+    corner_radius: f64,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+
+    // The panel never becomes the active app, so the material must not follow
+    // the window's active state or it would always render as inactive.
+    let material = material.filter(|_| !read_accessibility_prefs().reduce_transparency);
+    let effects = material.map(|material| {
+        EffectsBuilder::new()
+            .effect(material)
+            .state(EffectState::Active)
+            .radius(corner_radius)
+            .build()
+    });
+
+    window
+        .set_effects(effects)
+        .map_err(|e| format!("Failed to apply window effects: {}", e))
+}
+
+// =============================================================================
+// SYSTEM APPEARANCE
+// =============================================================================
+
+const DEFAULT_ACCENT_COLOR: &str = "#007AFF";
+
+/// Maps the `AppleAccentColor` default to the color System Settings shows.
+/// The key is absent when the accent is "Multicolor", which renders as blue.
+#[cfg(target_os = "macos")]
+fn accent_color_hex(value: Option<i64>) -> &'static str {
+    match value {
+        Some(-1) => "#8C8C8C",
+        Some(0) => "#FF5257",
+        Some(1) => "#F7821B",
+        Some(2) => "#FFC600",
+        Some(3) => "#62BA46",
+        Some(5) => "#A550A7",
+        Some(6) => "#F74F9E",
+        _ => DEFAULT_ACCENT_COLOR,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_system_appearance(_app: &AppHandle) -> SystemAppearance {
+    use crate::system_prefs;
+
+    // AppleInterfaceStyle is only present (as "Dark") in dark mode, including
+    // when Auto appearance has switched to dark for the evening.
+    let mode = match system_prefs::string_value(None, "AppleInterfaceStyle").as_deref() {
+        Some("Dark") => AppearanceMode::Dark,
+        _ => AppearanceMode::Light,
+    };
+    let accent_color = accent_color_hex(system_prefs::int_value(None, "AppleAccentColor"));
+
+    SystemAppearance {
+        mode,
+        accent_color: accent_color.to_string(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_system_appearance(app: &AppHandle) -> SystemAppearance {
+    let mode = match app
+        .get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+    {
+        Some(tauri::Theme::Light) => AppearanceMode::Light,
+        _ => AppearanceMode::Dark,
+    };
+
+    SystemAppearance {
+        mode,
+        accent_color: DEFAULT_ACCENT_COLOR.to_string(),
+    }
+}
+
+/// Point size of the system body text style at the default text size.
+#[cfg(target_os = "macos")]
+const DEFAULT_BODY_POINT_SIZE: f64 = 13.0;
+
+/// Size of the body text style relative to its default, which follows the
+/// Text Size setting on versions of macOS that have one.
+#[cfg(target_os = "macos")]
+fn preferred_text_scale() -> f64 {
+    use objc2::rc::autoreleasepool;
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {
+        static NSFontTextStyleBody: *const AnyObject;
+    }
+
+    // The dictionary and font are autoreleased, and the watcher thread has
+    // no pool of its own
+    autoreleasepool(|_| unsafe {
+        let options: *mut AnyObject = msg_send![class!(NSDictionary), dictionary];
+        let font: *mut AnyObject = msg_send![
+            class!(NSFont),
+            preferredFontForTextStyle: NSFontTextStyleBody,
+            options: options
+        ];
+        if font.is_null() {
+            return 1.0;
+        }
+        let point_size: f64 = msg_send![font, pointSize];
+        (point_size / DEFAULT_BODY_POINT_SIZE).max(1.0)
+    })
+}
+
+/// Reads the display switches from System Settings > Accessibility > Display
+/// and the preferred text size.
+#[cfg(target_os = "macos")]
+pub fn read_accessibility_prefs() -> AccessibilityPrefs {
+    use objc2::rc::autoreleasepool;
+    use objc2::runtime::{AnyObject, Bool};
+    use objc2::{class, msg_send};
+
+    autoreleasepool(|_| unsafe {
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return AccessibilityPrefs::default();
+        }
+        let reduce_motion: Bool = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+        let reduce_transparency: Bool =
+            msg_send![workspace, accessibilityDisplayShouldReduceTransparency];
+        let increase_contrast: Bool =
+            msg_send![workspace, accessibilityDisplayShouldIncreaseContrast];
+
+        AccessibilityPrefs {
+            reduce_motion: reduce_motion.as_bool(),
+            reduce_transparency: reduce_transparency.as_bool(),
+            increase_contrast: increase_contrast.as_bool(),
+            text_scale: preferred_text_scale(),
+        }
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn read_accessibility_prefs() -> AccessibilityPrefs {
+    AccessibilityPrefs::default()
+}
+
+/// Matches the window's native appearance to the system so materials that
+/// follow NSAppearance (popover, sidebar, ...) render in the right variant.
+fn sync_window_appearance(app: &AppHandle, appearance: &SystemAppearance) {
+    if let Some(window) = app.get_webview_window("main") {
+        let theme = match appearance.mode {
+            AppearanceMode::Light => tauri::Theme::Light,
+            AppearanceMode::Dark => tauri::Theme::Dark,
+        };
+        let _ = window.set_theme(Some(theme));
+    }
+}
+
+/// Watches for light/dark and accent color changes, emitting
+/// `appearance-changed` whenever either flips, and for the accessibility
+/// display switches, emitting `accessibility-prefs-changed`.
+pub fn start_appearance_watcher(app: AppHandle) {
+    runtime::spawn(app, "appearance", |app, shutdown| {
+        let mut current = read_system_appearance(app);
+        sync_window_appearance(app, &current);
+        let mut current_prefs = read_accessibility_prefs();
+
+        while shutdown.sleep(APPEARANCE_POLL_INTERVAL) {
+            let appearance = read_system_appearance(app);
+            if appearance != current {
+                sync_window_appearance(app, &appearance);
+                let _ = app.emit("appearance-changed", &appearance);
+                current = appearance;
+            }
+
+            let prefs = read_accessibility_prefs();
+            if prefs != current_prefs {
+                if prefs.reduce_transparency != current_prefs.reduce_transparency {
+                    // Re-applying the theme adds or drops its material
+                    theme::apply_active_theme(app, &app.state::<AppCore>());
+                }
+                let _ = app.emit("accessibility-prefs-changed", prefs);
+                current_prefs = prefs;
+            }
+        }
+    });
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn get_system_appearance(app: AppHandle) -> SystemAppearance {
+    read_system_appearance(&app)
+}
+
+#[tauri::command]
+pub fn get_accessibility_prefs() -> AccessibilityPrefs {
+    read_accessibility_prefs()
+}
+
+/// Sets the panel's background material (e.g. `"hudWindow"`, `"popover"`,
+/// `"sidebar"`, or `null` to remove it). The change is saved to the active
+/// theme so it survives restarts.
+#[tauri::command]
+pub fn set_background_material(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    material: Option<Effect>,
+    corner_radius: Option<f64>,
+) -> Result<(), String> {
+    theme::set_active_material(&app, &core, material, corner_radius)
+}
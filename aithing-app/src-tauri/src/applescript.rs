@@ -0,0 +1,355 @@
+//! AppleScript bridge
+//!
+//! Fixed, templated scripts for talking to Mail, Calendar, and Reminders.
+//! User and model supplied values are never spliced into script source: every
+//! script reads them from `argv`, so quotes or AppleScript syntax in a subject
+//! line can't change what the script does.
+//!
+//! Messages is intentionally not bridged: its scripting dictionary can only
+//! send, not create an unsent draft, and sending without review is not
+//! something an assistant should do.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde::Serialize;
+
+const DEFAULT_EVENT_RANGE_HOURS: u32 = 24;
+/// Calendar scans every calendar on each query, so keep the window bounded.
+const MAX_EVENT_RANGE_HOURS: u32 = 24 * 14;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MailMessage {
+    pub sender: String,
+    pub subject: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarEvent {
+    pub title: String,
+    /// Local time, "YYYY-MM-DDTHH:MM"
+    pub start: String,
+    /// Local time, "YYYY-MM-DDTHH:MM"
+    pub end: String,
+    pub calendar: String,
+}
+
+// =============================================================================
+// SCRIPTS
+// =============================================================================
+
+const CREATE_MAIL_DRAFT_SCRIPT: &str = r#"
+on run argv
+    set {recipientList, theSubject, theBody} to {item 1 of argv, item 2 of argv, item 3 of argv}
+    tell application "Mail"
+        set newMessage to make new outgoing message with properties {subject:theSubject, content:theBody, visible:true}
+        tell newMessage
+            repeat with recipientAddress in paragraphs of recipientList
+                if recipientAddress as text is not "" then
+                    make new to recipient at end of to recipients with properties {address:recipientAddress as text}
+                end if
+            end repeat
+        end tell
+        activate
+    end tell
+end run
+"#;
+
+const SELECTED_MAIL_SCRIPT: &str = r#"
+on run argv
+    tell application "Mail"
+        set selectedMessages to selection
+        if (count of selectedMessages) is 0 then return ""
+        set theMessage to item 1 of selectedMessages
+        return (sender of theMessage) & linefeed & (subject of theMessage) & linefeed & (content of theMessage)
+    end tell
+end run
+"#;
+
+/// Date conversion shared by the Calendar and Reminders scripts. Dates cross
+/// the argv boundary as numeric components and come back as
+/// "YYYY-MM-DDTHH:MM", so neither side depends on the user's date format.
+const DATE_HANDLERS: &str = r#"
+on makeDate(dateParts)
+    set theDate to current date
+    set day of theDate to 1
+    set year of theDate to (item 1 of dateParts) as integer
+    set month of theDate to (item 2 of dateParts) as integer
+    set day of theDate to (item 3 of dateParts) as integer
+    set time of theDate to ((item 4 of dateParts) as integer) * hours + ((item 5 of dateParts) as integer) * minutes
+    return theDate
+end makeDate
+
+on pad(value)
+    return text -2 thru -1 of ("0" & value)
+end pad
+
+on isoDate(theDate)
+    set theTime to time of theDate
+    return (year of theDate as text) & "-" & pad((month of theDate) as integer) & "-" & pad(day of theDate) & "T" & pad(theTime div hours) & ":" & pad((theTime mod hours) div minutes)
+end isoDate
+"#;
+
+const CREATE_CALENDAR_EVENT_SCRIPT: &str = r#"
+on run argv
+    set theTitle to item 1 of argv
+    set startDate to makeDate(items 2 thru 6 of argv)
+    set endDate to makeDate(items 7 thru 11 of argv)
+    set calendarName to item 12 of argv
+    tell application "Calendar"
+        if calendarName is "" then
+            set targetCalendar to first calendar whose writable is true
+        else
+            set targetCalendar to first calendar whose name is calendarName
+        end if
+        make new event at end of events of targetCalendar with properties {summary:theTitle, start date:startDate, end date:endDate}
+    end tell
+end run
+"#;
+
+/// Returns one "start\tend\tcalendar\ttitle" line per event overlapping the
+/// next `item 1 of argv` hours.
+const UPCOMING_EVENTS_SCRIPT: &str = r#"
+on run argv
+    set rangeStart to current date
+    set rangeEnd to rangeStart + ((item 1 of argv) as integer) * hours
+    set output to ""
+    tell application "Calendar"
+        repeat with theCalendar in calendars
+            set calendarName to name of theCalendar
+            repeat with theEvent in (every event of theCalendar whose start date < rangeEnd and end date > rangeStart)
+                set output to output & my isoDate(start date of theEvent) & tab & my isoDate(end date of theEvent) & tab & calendarName & tab & (summary of theEvent) & linefeed
+            end repeat
+        end repeat
+    end tell
+    return output
+end run
+"#;
+
+const CREATE_REMINDER_SCRIPT: &str = r#"
+on run argv
+    set theTitle to item 1 of argv
+    set listName to item 2 of argv
+    tell application "Reminders"
+        if listName is "" then
+            set targetList to default list
+        else
+            set targetList to list listName
+        end if
+        if (count of argv) > 2 then
+            set dueDate to my makeDate(items 3 thru 7 of argv)
+            make new reminder at end of reminders of targetList with properties {name:theTitle, due date:dueDate}
+        else
+            make new reminder at end of reminders of targetList with properties {name:theTitle}
+        end if
+    end tell
+end run
+"#;
+
+// =============================================================================
+// SCRIPT RUNNER
+// =============================================================================
+
+/// The `osascript` invocation for `script` with `args` as its `argv`. The
+/// `--` ends option parsing, so an argument like `-e` stays an argument
+/// instead of adding a line of script.
+#[cfg(any(target_os = "macos", test))]
+fn osascript_command(script: &str, args: &[&str]) -> std::process::Command {
+    let mut command = std::process::Command::new("osascript");
+    command.arg("-e").arg(script).arg("--").args(args);
+    command
+}
+
+/// Runs `script` with `osascript`, passing `args` as the script's `argv`.
+#[cfg(target_os = "macos")]
+pub fn run_script(script: &str, args: &[&str]) -> Result<String, String> {
+    let output = osascript_command(script, args)
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("AppleScript failed: {}", stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn run_script(_script: &str, _args: &[&str]) -> Result<String, String> {
+    Err("AppleScript is only available on macOS".to_string())
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Parses a local "YYYY-MM-DDTHH:MM[:SS]" timestamp.
+fn parse_local_datetime(value: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .map_err(|_| format!("Invalid date (expected YYYY-MM-DDTHH:MM): {}", value))
+}
+
+fn date_parts(value: &NaiveDateTime) -> [String; 5] {
+    [
+        value.year().to_string(),
+        value.month().to_string(),
+        value.day().to_string(),
+        value.hour().to_string(),
+        value.minute().to_string(),
+    ]
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Opens a new, unsent Mail message for the user to review.
+#[tauri::command]
+pub async fn create_mail_draft(
+    to: Vec<String>,
+    subject: String,
+    body: String,
+) -> Result<(), String> {
+    let recipients = to.join("\n");
+    run_script(CREATE_MAIL_DRAFT_SCRIPT, &[&recipients, &subject, &body]).map(|_| ())
+}
+
+/// Returns the message currently selected in Mail, if any, so a reply can be
+/// drafted from it.
+#[tauri::command]
+pub async fn get_selected_mail() -> Result<Option<MailMessage>, String> {
+    let output = run_script(SELECTED_MAIL_SCRIPT, &[])?;
+    if output.is_empty() {
+        return Ok(None);
+    }
+
+    let mut lines = output.splitn(3, '\n');
+    Ok(Some(MailMessage {
+        sender: lines.next().unwrap_or_default().to_string(),
+        subject: lines.next().unwrap_or_default().to_string(),
+        content: lines.next().unwrap_or_default().to_string(),
+    }))
+}
+
+/// Adds an event to Calendar. Times are local "YYYY-MM-DDTHH:MM"; the first
+/// writable calendar is used unless `calendar` names one.
+#[tauri::command]
+pub async fn create_calendar_event(
+    title: String,
+    start: String,
+    end: String,
+    calendar: Option<String>,
+) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Event title cannot be empty".to_string());
+    }
+
+    let start = parse_local_datetime(&start)?;
+    let end = parse_local_datetime(&end)?;
+    if end <= start {
+        return Err("Event must end after it starts".to_string());
+    }
+
+    let calendar = calendar.unwrap_or_default();
+    let start_parts = date_parts(&start);
+    let end_parts = date_parts(&end);
+
+    let mut args: Vec<&str> = vec![title];
+    args.extend(start_parts.iter().map(String::as_str));
+    args.extend(end_parts.iter().map(String::as_str));
+    args.push(&calendar);
+
+    run_script(
+        &[DATE_HANDLERS, CREATE_CALENDAR_EVENT_SCRIPT].concat(),
+        &args,
+    )
+    .map(|_| ())
+}
+
+/// Lists Calendar events overlapping the next `hours` (default 24), sorted by
+/// start time. Repeating events only appear for the occurrence Calendar
+/// stores, not for every expanded repeat.
+#[tauri::command]
+pub async fn get_upcoming_events(hours: Option<u32>) -> Result<Vec<CalendarEvent>, String> {
+    let hours = hours
+        .unwrap_or(DEFAULT_EVENT_RANGE_HOURS)
+        .clamp(1, MAX_EVENT_RANGE_HOURS);
+    let output = run_script(
+        &[DATE_HANDLERS, UPCOMING_EVENTS_SCRIPT].concat(),
+        &[&hours.to_string()],
+    )?;
+
+    let mut events: Vec<CalendarEvent> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            Some(CalendarEvent {
+                start: fields.next()?.to_string(),
+                end: fields.next()?.to_string(),
+                calendar: fields.next()?.to_string(),
+                title: fields.next()?.to_string(),
+            })
+        })
+        .collect();
+    events.sort_by(|a, b| a.start.cmp(&b.start));
+    Ok(events)
+}
+
+/// Adds a reminder, optionally due at a local "YYYY-MM-DDTHH:MM" time, to the
+/// default Reminders list unless `list` names one.
+#[tauri::command]
+pub async fn create_reminder(
+    title: String,
+    due: Option<String>,
+    list: Option<String>,
+) -> Result<(), String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Reminder title cannot be empty".to_string());
+    }
+
+    let list = list.unwrap_or_default();
+    let due_parts = due
+        .as_deref()
+        .map(parse_local_datetime)
+        .transpose()?
+        .map(|due| date_parts(&due));
+
+    let mut args: Vec<&str> = vec![title, &list];
+    if let Some(due_parts) = &due_parts {
+        args.extend(due_parts.iter().map(String::as_str));
+    }
+
+    run_script(&[DATE_HANDLERS, CREATE_REMINDER_SCRIPT].concat(), &args).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_follow_the_end_of_options() {
+        let command = osascript_command("on run argv\nend run", &["-e", "do shell script \"id\""]);
+        let args: Vec<_> = command.get_args().collect();
+
+        assert_eq!(command.get_program(), "osascript");
+        assert_eq!(
+            args,
+            [
+                "-e",
+                "on run argv\nend run",
+                "--",
+                "-e",
+                "do shell script \"id\""
+            ]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Browser tab context
+//!
+//! Reads the URL and title of the active tab in the frontmost browser so
+//! "summarize this page" works without a browser extension. AppleScript is
+//! used where the browser allows it; if automation is denied, the focused
+//! window is read through Accessibility instead.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::active_app;
+use crate::app_core::AppCore;
+use crate::applescript;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrowserContext {
+    /// Bundle ID of the browser the context was read from
+    pub browser: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+}
+
+// =============================================================================
+// SCRIPTS
+// =============================================================================
+
+const SAFARI_SCRIPT: &str = r#"
+tell application "Safari"
+    set theTab to current tab of front window
+    return (URL of theTab) & linefeed & (name of theTab)
+end tell
+"#;
+
+const CHROME_SCRIPT: &str = r#"
+tell application "Google Chrome"
+    set theTab to active tab of front window
+    return (URL of theTab) & linefeed & (title of theTab)
+end tell
+"#;
+
+const ARC_SCRIPT: &str = r#"
+tell application "Arc"
+    set theTab to active tab of front window
+    return (URL of theTab) & linefeed & (title of theTab)
+end tell
+"#;
+
+/// Bundle ID -> script returning "URL\ntitle" for the active tab.
+const BROWSERS: &[(&str, &str)] = &[
+    ("com.apple.Safari", SAFARI_SCRIPT),
+    ("com.google.Chrome", CHROME_SCRIPT),
+    ("company.thebrowser.Browser", ARC_SCRIPT),
+];
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Falls back to the focused window's title (and document URL, where the
+/// browser exposes one) when AppleScript isn't permitted.
+#[cfg(target_os = "macos")]
+fn read_via_accessibility() -> Option<(Option<String>, Option<String>)> {
+    use crate::accessibility;
+
+    let pid = active_app::frontmost_pid()?;
+    let url = accessibility::focused_window_string(pid, "AXDocument");
+    let title = accessibility::focused_window_string(pid, "AXTitle");
+    (url.is_some() || title.is_some()).then_some((url, title))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_via_accessibility() -> Option<(Option<String>, Option<String>)> {
+    None
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns the active tab of the frontmost browser, or `None` when the
+/// frontmost app isn't a supported browser or is on the exclusion list.
+#[tauri::command]
+pub async fn get_browser_context(
+    core: State<'_, AppCore>,
+) -> Result<Option<BrowserContext>, String> {
+    let Some(bundle_id) = active_app::frontmost_bundle_id() else {
+        return Ok(None);
+    };
+    if active_app::is_frontmost_app_excluded(&core) {
+        return Ok(None);
+    }
+    let Some((_, script)) = BROWSERS
+        .iter()
+        .find(|(browser, _)| browser.eq_ignore_ascii_case(&bundle_id))
+    else {
+        return Ok(None);
+    };
+
+    let (url, title) = match applescript::run_script(script, &[]) {
+        Ok(output) => {
+            let (url, title) = output.split_once('\n').unwrap_or((&output, ""));
+            (non_empty(url), non_empty(title))
+        }
+        Err(e) => read_via_accessibility().ok_or(e)?,
+    };
+
+    Ok(Some(BrowserContext {
+        browser: bundle_id,
+        url,
+        title,
+    }))
+}
@@ -0,0 +1,723 @@
+//! Local calculator for instant answers
+//!
+//! Evaluates arithmetic expressions ("(3 + 4) * 2^3", "sqrt(2) / 2") and unit
+//! conversions ("5 km to mi", "100 f in c") entirely offline, so simple queries
+//! typed into the panel can be answered before a model is ever involved.
+//! Currency conversions ("100 usd to eur") use the ECB's daily reference
+//! rates. They're off unless `currency_rates_enabled` is set, and the rates
+//! are fetched only for queries between two currencies the ECB publishes,
+//! then cached for an hour.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::providers::HTTP_CLIENT;
+use crate::vcr;
+
+/// Reference rates are published once per working day
+const RATES_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const RATES_URL: &str = "https://api.frankfurter.app/latest";
+/// ISO 4217 codes of the currencies the ECB publishes reference rates for.
+#[rustfmt::skip]
+const CURRENCIES: &[&str] = &[
+    "AUD", "BGN", "BRL", "CAD", "CHF", "CNY", "CZK", "DKK", "EUR", "GBP", "HKD",
+    "HUF", "IDR", "ILS", "INR", "ISK", "JPY", "KRW", "MXN", "MYR", "NOK", "NZD",
+    "PHP", "PLN", "RON", "SEK", "SGD", "THB", "TRY", "USD", "ZAR",
+];
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalcResult {
+    pub expression: String,
+    pub value: f64,
+    pub unit: Option<String>,
+    pub formatted: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Time,
+    Data,
+    Temperature,
+}
+
+/// A unit is converted to its dimension's base unit as `value * factor + offset`.
+/// The offset is only non-zero for temperatures (base unit: kelvin).
+struct Unit {
+    names: &'static [&'static str],
+    symbol: &'static str,
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(
+    names: &'static [&'static str],
+    symbol: &'static str,
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        names,
+        symbol,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+#[rustfmt::skip]
+static UNITS: &[Unit] = &[
+    // Length (metre)
+    unit(&["mm", "millimeter", "millimeters", "millimetre", "millimetres"], "mm", Dimension::Length, 0.001),
+    unit(&["cm", "centimeter", "centimeters", "centimetre", "centimetres"], "cm", Dimension::Length, 0.01),
+    unit(&["m", "meter", "meters", "metre", "metres"], "m", Dimension::Length, 1.0),
+    unit(&["km", "kilometer", "kilometers", "kilometre", "kilometres"], "km", Dimension::Length, 1000.0),
+    unit(&["in", "inch", "inches"], "in", Dimension::Length, 0.0254),
+    unit(&["ft", "foot", "feet"], "ft", Dimension::Length, 0.3048),
+    unit(&["yd", "yard", "yards"], "yd", Dimension::Length, 0.9144),
+    unit(&["mi", "mile", "miles"], "mi", Dimension::Length, 1609.344),
+    unit(&["nmi", "nauticalmile", "nauticalmiles"], "nmi", Dimension::Length, 1852.0),
+    // Mass (kilogram)
+    unit(&["mg", "milligram", "milligrams"], "mg", Dimension::Mass, 1e-6),
+    unit(&["g", "gram", "grams"], "g", Dimension::Mass, 0.001),
+    unit(&["kg", "kilogram", "kilograms", "kilo", "kilos"], "kg", Dimension::Mass, 1.0),
+    unit(&["t", "tonne", "tonnes"], "t", Dimension::Mass, 1000.0),
+    unit(&["oz", "ounce", "ounces"], "oz", Dimension::Mass, 0.028_349_523_125),
+    unit(&["lb", "lbs", "pound", "pounds"], "lb", Dimension::Mass, 0.453_592_37),
+    unit(&["st", "stone", "stones"], "st", Dimension::Mass, 6.350_293_18),
+    // Volume (litre)
+    unit(&["ml", "milliliter", "milliliters", "millilitre", "millilitres"], "ml", Dimension::Volume, 0.001),
+    unit(&["cl", "centiliter", "centiliters", "centilitre", "centilitres"], "cl", Dimension::Volume, 0.01),
+    unit(&["l", "liter", "liters", "litre", "litres"], "l", Dimension::Volume, 1.0),
+    unit(&["tsp", "teaspoon", "teaspoons"], "tsp", Dimension::Volume, 0.004_928_921_593_75),
+    unit(&["tbsp", "tablespoon", "tablespoons"], "tbsp", Dimension::Volume, 0.014_786_764_781_25),
+    unit(&["floz", "fluidounce", "fluidounces"], "fl oz", Dimension::Volume, 0.029_573_529_562_5),
+    unit(&["cup", "cups"], "cup", Dimension::Volume, 0.236_588_236_5),
+    unit(&["pt", "pint", "pints"], "pt", Dimension::Volume, 0.473_176_473),
+    unit(&["qt", "quart", "quarts"], "qt", Dimension::Volume, 0.946_352_946),
+    unit(&["gal", "gallon", "gallons"], "gal", Dimension::Volume, 3.785_411_784),
+    // Time (second)
+    unit(&["ms", "millisecond", "milliseconds"], "ms", Dimension::Time, 0.001),
+    unit(&["s", "sec", "secs", "second", "seconds"], "s", Dimension::Time, 1.0),
+    unit(&["min", "mins", "minute", "minutes"], "min", Dimension::Time, 60.0),
+    unit(&["h", "hr", "hrs", "hour", "hours"], "h", Dimension::Time, 3600.0),
+    unit(&["d", "day", "days"], "d", Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], "wk", Dimension::Time, 604_800.0),
+    unit(&["yr", "year", "years"], "yr", Dimension::Time, 31_557_600.0),
+    // Data (byte)
+    unit(&["bit", "bits"], "bit", Dimension::Data, 0.125),
+    unit(&["b", "byte", "bytes"], "B", Dimension::Data, 1.0),
+    unit(&["kb", "kilobyte", "kilobytes"], "KB", Dimension::Data, 1e3),
+    unit(&["mb", "megabyte", "megabytes"], "MB", Dimension::Data, 1e6),
+    unit(&["gb", "gigabyte", "gigabytes"], "GB", Dimension::Data, 1e9),
+    unit(&["tb", "terabyte", "terabytes"], "TB", Dimension::Data, 1e12),
+    unit(&["kib", "kibibyte", "kibibytes"], "KiB", Dimension::Data, 1024.0),
+    unit(&["mib", "mebibyte", "mebibytes"], "MiB", Dimension::Data, 1_048_576.0),
+    unit(&["gib", "gibibyte", "gibibytes"], "GiB", Dimension::Data, 1_073_741_824.0),
+    unit(&["tib", "tebibyte", "tebibytes"], "TiB", Dimension::Data, 1_099_511_627_776.0),
+    // Temperature (kelvin)
+    Unit {
+        names: &["c", "°c", "celsius"],
+        symbol: "°C",
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["f", "°f", "fahrenheit"],
+        symbol: "°F",
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+    },
+    unit(&["k", "kelvin"], "K", Dimension::Temperature, 1.0),
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = name.trim().to_lowercase();
+    UNITS
+        .iter()
+        .find(|unit| unit.names.contains(&name.as_str()))
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct CalcState {
+    /// Units of each currency per euro, with when they were fetched
+    rates: RwLock<Option<(Instant, HashMap<String, f64>)>>,
+}
+
+// =============================================================================
+// EXPRESSION PARSER
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Option<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Scientific notation: 1.5e3, 2E-4
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        while j < chars.len() && chars[j].is_ascii_digit() {
+                            j += 1;
+                        }
+                        i = j;
+                    }
+                }
+                let literal: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(literal.parse().ok()?));
+            }
+            c if c.is_alphabetic() || c == 'π' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == 'π') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(
+                    chars[start..i].iter().collect::<String>().to_lowercase(),
+                ));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                tokens.push(Token::Op('^'));
+                i += 2;
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Recursive-descent parser over the token stream. `operations` counts binary
+/// operators and function calls so a bare number isn't reported as an answer.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    operations: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            self.operations += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Some(value)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.pos += 1;
+            self.operations += 1;
+            let rhs = self.unary()?;
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Some(value)
+    }
+
+    // unary := ('-' | '+') unary | power
+    fn unary(&mut self) -> Option<f64> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Some(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    // power := primary ('^' unary)?   (right-associative)
+    fn power(&mut self) -> Option<f64> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            self.operations += 1;
+            let exponent = self.unary()?;
+            return Some(base.powf(exponent));
+        }
+        Some(base)
+    }
+
+    // primary := number | constant | function '(' expr ')' | '(' expr ')'
+    fn primary(&mut self) -> Option<f64> {
+        match self.next()? {
+            Token::Number(value) => Some(value),
+            Token::LParen => {
+                let value = self.expr()?;
+                match self.next()? {
+                    Token::RParen => Some(value),
+                    _ => None,
+                }
+            }
+            Token::Ident(name) => {
+                if let Some(value) = constant(&name) {
+                    return Some(value);
+                }
+                if self.next()? != Token::LParen {
+                    return None;
+                }
+                let arg = self.expr()?;
+                if self.next()? != Token::RParen {
+                    return None;
+                }
+                self.operations += 1;
+                apply_function(&name, arg)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        "tau" => Some(std::f64::consts::TAU),
+        _ => None,
+    }
+}
+
+fn apply_function(name: &str, arg: f64) -> Option<f64> {
+    let value = match name {
+        "sqrt" => arg.sqrt(),
+        "cbrt" => arg.cbrt(),
+        "abs" => arg.abs(),
+        "ln" => arg.ln(),
+        "log" => arg.log10(),
+        "log2" => arg.log2(),
+        "exp" => arg.exp(),
+        "sin" => arg.sin(),
+        "cos" => arg.cos(),
+        "tan" => arg.tan(),
+        "asin" => arg.asin(),
+        "acos" => arg.acos(),
+        "atan" => arg.atan(),
+        "floor" => arg.floor(),
+        "ceil" => arg.ceil(),
+        "round" => arg.round(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Evaluates an arithmetic expression, returning the value and the number of
+/// operations it contained.
+fn evaluate_arithmetic(input: &str) -> Option<(f64, usize)> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return None;
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        operations: 0,
+    };
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() || !value.is_finite() {
+        return None;
+    }
+    Some((value, parser.operations))
+}
+
+// =============================================================================
+// UNIT CONVERSION
+// =============================================================================
+
+const CONVERSION_SEPARATORS: [&str; 4] = [" to ", " in ", " as ", " -> "];
+
+/// Splits "<expression> <word>" into its parts, e.g. "(2 + 3) ft" or "5km".
+fn split_trailing_word(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    let word_start = input
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphabetic() || *c == '°')
+        .last()
+        .map(|(i, _)| i)?;
+    let amount = input[..word_start].trim();
+    if amount.is_empty() {
+        return None;
+    }
+    Some((amount, &input[word_start..]))
+}
+
+fn split_quantity(input: &str) -> Option<(&str, &'static Unit)> {
+    let (amount, unit) = split_trailing_word(input)?;
+    Some((amount, find_unit(unit)?))
+}
+
+fn evaluate_conversion(input: &str) -> Option<(f64, &'static Unit)> {
+    for separator in CONVERSION_SEPARATORS {
+        let Some((lhs, rhs)) = input.rsplit_once(separator) else {
+            continue;
+        };
+        let (Some((amount, from)), Some(to)) = (split_quantity(lhs), find_unit(rhs)) else {
+            continue;
+        };
+        if from.dimension != to.dimension {
+            continue;
+        }
+        // Another separator may still split off a valid amount
+        let Some((value, _)) = evaluate_arithmetic(amount) else {
+            continue;
+        };
+        let base = value * from.factor + from.offset;
+        return Some(((base - to.offset) / to.factor, to));
+    }
+    None
+}
+
+// =============================================================================
+// CURRENCY CONVERSION
+// =============================================================================
+
+#[derive(Deserialize)]
+struct RatesResponse {
+    base: String,
+    rates: HashMap<String, f64>,
+}
+
+/// The uppercased code if `word` names a currency in `CURRENCIES`.
+fn currency_code(word: &str) -> Option<String> {
+    let code = word.trim().to_uppercase();
+    CURRENCIES.contains(&code.as_str()).then_some(code)
+}
+
+/// Splits "<expression> <code> to <code>" into the amount and the two
+/// uppercased ISO 4217 codes, when both are currencies in `CURRENCIES`.
+fn split_currency_query(input: &str) -> Option<(&str, String, String)> {
+    CONVERSION_SEPARATORS.iter().find_map(|separator| {
+        let (lhs, rhs) = input.rsplit_once(separator)?;
+        let (amount, from) = split_trailing_word(lhs)?;
+        Some((amount, currency_code(from)?, currency_code(rhs)?))
+    })
+}
+
+/// Converts through the rates' common base currency.
+fn convert_currency(
+    amount: f64,
+    from: &str,
+    to: &str,
+    rates: &HashMap<String, f64>,
+) -> Option<f64> {
+    let from_rate = rates.get(from)?;
+    let to_rate = rates.get(to)?;
+    if *from_rate <= 0.0 {
+        return None;
+    }
+    Some(amount / from_rate * to_rate)
+}
+
+async fn fetch_rates(core: &AppCore) -> Result<HashMap<String, f64>, String> {
+    if let Some((fetched_at, rates)) = core.calc.rates.read().as_ref() {
+        if fetched_at.elapsed() < RATES_CACHE_TTL {
+            return Ok(rates.clone());
+        }
+    }
+
+    let request = HTTP_CLIENT
+        .get(RATES_URL)
+        .build()
+        .map_err(|e| format!("Failed to build rates request: {}", e))?;
+    let (status, body) = vcr::send(core, request).await?;
+    if !status.is_success() {
+        return Err(format!("Exchange rates returned {}", status));
+    }
+    let response: RatesResponse = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse exchange rates: {}", e))?;
+
+    // The base currency isn't listed among its own rates
+    let mut rates = response.rates;
+    rates.insert(response.base, 1.0);
+    *core.calc.rates.write() = Some((Instant::now(), rates.clone()));
+    Ok(rates)
+}
+
+async fn evaluate_currency(core: &AppCore, text: &str) -> Result<Option<CalcResult>, String> {
+    if !core.settings.read().currency_rates_enabled {
+        return Ok(None);
+    }
+    let expression = trim_expression(text);
+    let Some((amount, from, to)) = split_currency_query(expression) else {
+        return Ok(None);
+    };
+    let Some((amount, _)) = evaluate_arithmetic(amount) else {
+        return Ok(None);
+    };
+    let rates = fetch_rates(core).await?;
+    let Some(value) = convert_currency(amount, &from, &to, &rates) else {
+        return Ok(None);
+    };
+    Ok(Some(CalcResult {
+        expression: expression.to_string(),
+        value,
+        unit: Some(to.clone()),
+        formatted: format!("{:.2} {}", value, to),
+    }))
+}
+
+// =============================================================================
+// FORMATTING
+// =============================================================================
+
+fn format_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if value == value.trunc() && magnitude < 1e15 {
+        return format!("{}", value as i64);
+    }
+    if magnitude >= 1e15 || magnitude < 1e-6 {
+        let formatted = format!("{:.6e}", value);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+    let formatted = format!("{:.10}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+// =============================================================================
+// PUBLIC API
+// =============================================================================
+
+/// Strips the "=" and "?" people type around a calculation.
+fn trim_expression(text: &str) -> &str {
+    text.trim()
+        .trim_start_matches('=')
+        .trim_end_matches(['=', '?'])
+        .trim()
+}
+
+/// Evaluates `text` as a calculation or unit conversion. Returns `None` when the
+/// text isn't something the calculator can answer, so callers can fall through
+/// to the model.
+pub fn evaluate(text: &str) -> Option<CalcResult> {
+    let expression = trim_expression(text);
+    if expression.is_empty() {
+        return None;
+    }
+
+    if let Some((value, unit)) = evaluate_conversion(expression) {
+        let number = format_number(value);
+        return Some(CalcResult {
+            expression: expression.to_string(),
+            value,
+            unit: Some(unit.symbol.to_string()),
+            formatted: format!("{} {}", number, unit.symbol),
+        });
+    }
+
+    // A lone number or constant isn't a question worth answering locally
+    let (value, operations) = evaluate_arithmetic(expression)?;
+    if operations == 0 {
+        return None;
+    }
+
+    Some(CalcResult {
+        expression: expression.to_string(),
+        value,
+        unit: None,
+        formatted: format_number(value),
+    })
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Answers `text` locally when it's a calculation, unit conversion, or
+/// currency conversion. Only currency conversions touch the network, and
+/// only once the user has turned them on.
+#[tauri::command]
+pub async fn evaluate_expression(
+    core: State<'_, AppCore>,
+    text: String,
+) -> Result<Option<CalcResult>, String> {
+    if let Some(result) = evaluate(&text) {
+        return Ok(Some(result));
+    }
+    evaluate_currency(&core, &text).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(text: &str) -> Option<String> {
+        evaluate(text).map(|result| result.formatted)
+    }
+
+    #[test]
+    fn operators_follow_precedence() {
+        for (text, expected) in [
+            ("2 + 3 * 4", "14"),
+            ("(2 + 3) * 4", "20"),
+            ("10 - 4 - 3", "3"),
+            ("2 ^ 3 ^ 2", "512"),
+            ("2 ** 10", "1024"),
+            ("7 % 4 + 1", "4"),
+            ("12 ÷ 4 × 3", "9"),
+            ("sqrt(16) + 1", "5"),
+            ("= 1.5e3 / 3 ?", "500"),
+        ] {
+            assert_eq!(formatted(text).as_deref(), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn unary_minus_binds_tighter_than_operators_but_not_powers() {
+        for (text, expected) in [
+            ("-3 + 5", "2"),
+            ("4 * -2", "-8"),
+            ("--2 + 1", "3"),
+            ("-2 ^ 2", "-4"),
+            ("(-2) ^ 2", "4"),
+            ("2 ^ -1", "0.5"),
+        ] {
+            assert_eq!(formatted(text).as_deref(), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn non_answers_fall_through() {
+        for text in [
+            "1 / 0",
+            "0 / 0",
+            "sqrt(-1) + 1",
+            "42",
+            "pi",
+            "(2 + 3",
+            "2 +",
+            "what is love",
+            "",
+        ] {
+            assert!(evaluate(text).is_none(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn units_convert_within_a_dimension() {
+        for (text, expected) in [
+            ("5 km to mi", "3.1068559612 mi"),
+            ("100 f in c", "37.7777777778 °C"),
+            ("0 c to k", "273.15 K"),
+            ("(2 + 3) ft in m", "1.524 m"),
+            ("1kg as lbs", "2.2046226218 lb"),
+            ("12 in in cm", "30.48 cm"),
+            ("1 gib to mb", "1073.741824 MB"),
+            ("90 min -> h", "1.5 h"),
+        ] {
+            assert_eq!(formatted(text).as_deref(), Some(expected), "{}", text);
+        }
+        assert!(evaluate("5 km to kg").is_none());
+    }
+
+    #[test]
+    fn currency_queries_convert_through_the_base_rate() {
+        let rates = HashMap::from([
+            ("EUR".to_string(), 1.0),
+            ("USD".to_string(), 1.25),
+            ("GBP".to_string(), 0.8),
+        ]);
+
+        for (text, from, to, expected) in [
+            ("100 usd to eur", "USD", "EUR", 80.0),
+            ("(20 + 30) GBP in USD", "GBP", "USD", 78.125),
+            ("10eur as gbp", "EUR", "GBP", 8.0),
+        ] {
+            let (amount, parsed_from, parsed_to) = split_currency_query(text).unwrap();
+            assert_eq!((parsed_from.as_str(), parsed_to.as_str()), (from, to));
+            let (amount, _) = evaluate_arithmetic(amount).unwrap();
+            let value = convert_currency(amount, from, to, &rates).unwrap();
+            assert!((value - expected).abs() < 1e-9, "{}", text);
+        }
+
+        assert!(split_currency_query("100 dollars to eur").is_none());
+        // Three letters aren't enough; the code has to be a known currency
+        assert!(split_currency_query("10 abc to xyz").is_none());
+        assert!(split_currency_query("5 day to cad").is_none());
+        assert!(convert_currency(1.0, "USD", "XYZ", &rates).is_none());
+    }
+}
@@ -0,0 +1,135 @@
+//! Model capability registry
+//!
+//! What each model can do (images, tools, JSON mode), how much it can take,
+//! and what it costs, from a manifest bundled with the app. Token limits the
+//! provider reports in its live catalog take precedence over the manifest.
+//! The frontend checks this before sending attachments a model can't read.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::providers;
+
+const MANIFEST: &str = include_str!("../model_capabilities.json");
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Pricing {
+    /// USD per million input tokens
+    pub input_per_mtok: f64,
+    /// USD per million output tokens
+    pub output_per_mtok: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCapabilities {
+    pub vision: bool,
+    pub tools: bool,
+    pub json_mode: bool,
+    pub context_window: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    pub pricing: Option<Pricing>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    /// Model ID prefix -> capabilities, so dated snapshots
+    /// ("claude-sonnet-4-20250514") share their family's entry
+    models: HashMap<String, ModelCapabilities>,
+}
+
+// =============================================================================
+// GLOBAL STATE
+// =============================================================================
+
+static REGISTRY: Lazy<HashMap<String, ModelCapabilities>> = Lazy::new(|| {
+    serde_json::from_str::<Manifest>(MANIFEST)
+        .map(|manifest| manifest.models)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to parse model capability manifest: {}", e);
+            HashMap::new()
+        })
+});
+
+// =============================================================================
+// LOOKUP
+// =============================================================================
+
+/// Finds the manifest entry with the longest prefix of `model`, so
+/// "gpt-4o-mini" isn't mistaken for "gpt-4o".
+pub fn capabilities_for(core: &AppCore, model: &str) -> Option<ModelCapabilities> {
+    let mut capabilities = REGISTRY
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, capabilities)| capabilities.clone())?;
+
+    if let Some(live) = providers::cached_model(core, model) {
+        capabilities.context_window = live.context_window.or(capabilities.context_window);
+        capabilities.max_output_tokens = live.max_output_tokens.or(capabilities.max_output_tokens);
+    }
+    Some(capabilities)
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns what `model` supports, or `None` for models the registry doesn't
+/// know, which callers should treat as "don't gate".
+#[tauri::command]
+pub fn get_model_capabilities(
+    core: State<'_, AppCore>,
+    model: String,
+) -> Option<ModelCapabilities> {
+    capabilities_for(&core, &model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ModelInfo, Provider};
+
+    #[test]
+    fn longest_prefix_wins() {
+        let core = AppCore::default();
+        let mini = capabilities_for(&core, "gpt-4o-mini-2024-07-18").unwrap();
+        let full = capabilities_for(&core, "gpt-4o-2024-08-06").unwrap();
+
+        let price = |capabilities: &ModelCapabilities| capabilities.pricing.unwrap().input_per_mtok;
+        assert!(price(&mini) < price(&full));
+    }
+
+    #[test]
+    fn unknown_models_are_not_gated() {
+        let core = AppCore::default();
+        assert!(capabilities_for(&core, "some-local-model").is_none());
+    }
+
+    #[test]
+    fn live_token_limits_take_precedence() {
+        let core = AppCore::default();
+        providers::cache_models(
+            &core,
+            Provider::Google,
+            vec![ModelInfo {
+                id: "gemini-2.0-flash-001".to_string(),
+                name: "Gemini 2.0 Flash".to_string(),
+                provider: Provider::Google,
+                context_window: Some(2_000_000),
+                max_output_tokens: None,
+            }],
+        );
+
+        let capabilities = capabilities_for(&core, "gemini-2.0-flash-001").unwrap();
+        assert_eq!(capabilities.context_window, Some(2_000_000));
+        assert_eq!(capabilities.max_output_tokens, Some(8192));
+    }
+}
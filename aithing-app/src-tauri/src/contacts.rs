@@ -0,0 +1,121 @@
+//! Contact lookup
+//!
+//! Finds email addresses and phone numbers in Contacts so drafting actions
+//! can fill in recipients. Off unless enabled in settings, and every lookup
+//! asks the user first in a native dialog the frontend can't answer for them.
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::app_core::AppCore;
+use crate::applescript;
+use crate::i18n::t;
+
+/// More matches than this means the query was too vague to be useful.
+const MAX_CONTACTS: usize = 10;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Contact {
+    pub name: String,
+    pub emails: Vec<String>,
+    pub phones: Vec<String>,
+}
+
+// =============================================================================
+// SCRIPTS
+// =============================================================================
+
+/// Returns tab-separated "name", "email", and "phone" lines, each person's
+/// name line followed by their addresses and numbers.
+const LOOKUP_CONTACT_SCRIPT: &str = r#"
+on run argv
+    set output to ""
+    tell application "Contacts"
+        repeat with thePerson in (every person whose name contains (item 1 of argv))
+            set output to output & "name" & tab & (name of thePerson) & linefeed
+            repeat with theEmail in emails of thePerson
+                set output to output & "email" & tab & (value of theEmail) & linefeed
+            end repeat
+            repeat with thePhone in phones of thePerson
+                set output to output & "phone" & tab & (value of thePhone) & linefeed
+            end repeat
+        end repeat
+    end tell
+    return output
+end run
+"#;
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn parse_contacts(output: &str) -> Vec<Contact> {
+    let mut contacts: Vec<Contact> = Vec::new();
+
+    for line in output.lines() {
+        let Some((kind, value)) = line.split_once('\t') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+
+        match (kind, contacts.last_mut()) {
+            ("name", _) => contacts.push(Contact {
+                name: value,
+                emails: Vec::new(),
+                phones: Vec::new(),
+            }),
+            ("email", Some(contact)) => contact.emails.push(value),
+            ("phone", Some(contact)) => contact.phones.push(value),
+            _ => {}
+        }
+    }
+
+    contacts.truncate(MAX_CONTACTS);
+    contacts
+}
+
+fn confirm_lookup(app: &AppHandle, core: &AppCore, name: &str) -> bool {
+    app.dialog()
+        .message(t(core, "contact-lookup-message", &[("name", name)]))
+        .title(t(core, "contact-lookup-title", &[]))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            t(core, "contact-lookup-allow", &[]),
+            t(core, "contact-lookup-deny", &[]),
+        ))
+        .blocking_show()
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Looks up contacts whose name contains `name`. Fails if contact lookup is
+/// disabled in settings or the user declines this lookup.
+#[tauri::command]
+pub async fn lookup_contact(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    name: String,
+) -> Result<Vec<Contact>, String> {
+    if !core.settings.read().contacts_lookup_enabled {
+        return Err(t(&core, "contact-lookup-disabled", &[]));
+    }
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Contact name cannot be empty".to_string());
+    }
+
+    if !confirm_lookup(&app, &core, name) {
+        return Err(t(&core, "contact-lookup-declined", &[]));
+    }
+
+    let output = applescript::run_script(LOOKUP_CONTACT_SCRIPT, &[name])?;
+    Ok(parse_contacts(&output))
+}
@@ -0,0 +1,425 @@
+//! Support diagnostics
+//!
+//! `run_diagnostics` checks what usually goes wrong on a user's machine and
+//! returns one report the support UI can show or copy. The checks cover
+//! provider reachability and credentials, macOS privacy permissions, the
+//! settings store, global shortcut registration, background tasks, and
+//! free disk space. Checks only read state; none of them changes anything.
+//!
+//! AIThing keeps no Keychain items and no database, so those checks are
+//! reported as skipped rather than left out, and support can tell "not
+//! applicable" from "not checked".
+
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::hardware::{self, HardwareInfo};
+use crate::providers::{self, Provider};
+use crate::runtime::{self, TaskStatus};
+use crate::secrets::{self, CredentialSource};
+use crate::{shortcuts, STORE_FILE};
+
+/// Per-provider reachability timeout, shorter than a normal request.
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Free space below this fails the disk check.
+const DISK_FAIL_BYTES: u64 = 200 * 1024 * 1024;
+/// Free space below this warns.
+const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+/// Ordered from best to worst, so the report's status is the maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Skipped,
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    /// Stable identifier, e.g. `provider.anthropic`
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    /// Worst status among the checks
+    pub status: CheckStatus,
+    pub version: String,
+    pub os: String,
+    pub hardware: HardwareInfo,
+    /// RFC 3339 local time the report was made
+    pub generated_at: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+fn check(id: &str, label: &str, status: CheckStatus, detail: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+// =============================================================================
+// CHECKS
+// =============================================================================
+
+fn provider_label(provider: Provider) -> &'static str {
+    match provider {
+        Provider::Anthropic => "Anthropic",
+        Provider::OpenAI => "OpenAI",
+        Provider::Google => "Google",
+    }
+}
+
+/// Whether the provider's API answers at all; any HTTP response counts.
+/// Goes straight to the network, bypassing recording and replay.
+async fn check_provider(core: &AppCore, provider: Provider) -> DiagnosticCheck {
+    let label = provider_label(provider);
+    let id = format!("provider.{}", label.to_lowercase());
+    let credentials = secrets::resolve(core, provider, None);
+    let base_url = credentials
+        .base_url
+        .unwrap_or_else(|| providers::default_base_url(provider).to_string());
+
+    let response = providers::HTTP_CLIENT
+        .head(&base_url)
+        .timeout(REACHABILITY_TIMEOUT)
+        .send()
+        .await;
+    if let Err(e) = response {
+        return check(
+            &id,
+            label,
+            CheckStatus::Fail,
+            format!("Can't reach {}: {}", base_url, e),
+        );
+    }
+
+    // Keys saved in the panel stay in the webview, so no source here only
+    // means none is set outside the app
+    let key = match credentials.source {
+        Some(CredentialSource::App) => "key saved in the app",
+        Some(CredentialSource::Environment) => "key from the environment",
+        Some(CredentialSource::EnvFile) => "key from a .env file",
+        None => "no key outside the app",
+    };
+    check(
+        &id,
+        label,
+        CheckStatus::Pass,
+        format!("{} is reachable; {}", base_url, key),
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn check_permissions() -> Vec<DiagnosticCheck> {
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+    }
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOHIDCheckAccess(request_type: u32) -> u32;
+    }
+    // kIOHIDRequestTypeListenEvent, kIOHIDAccessTypeGranted
+    const LISTEN_EVENT: u32 = 1;
+    const ACCESS_GRANTED: u32 = 0;
+
+    let permission = |id: &str, label: &str, granted: bool, needed_for: &str| {
+        if granted {
+            check(id, label, CheckStatus::Pass, "Granted")
+        } else {
+            check(
+                id,
+                label,
+                CheckStatus::Warn,
+                format!("Not granted; needed for {}", needed_for),
+            )
+        }
+    };
+
+    vec![
+        permission(
+            "permission.accessibility",
+            "Accessibility",
+            crate::accessibility::is_trusted(),
+            "reading text from other apps",
+        ),
+        permission(
+            "permission.screen_recording",
+            "Screen Recording",
+            unsafe { CGPreflightScreenCaptureAccess() },
+            "window capture",
+        ),
+        permission(
+            "permission.input_monitoring",
+            "Input Monitoring",
+            unsafe { IOHIDCheckAccess(LISTEN_EVENT) } == ACCESS_GRANTED,
+            "double-tap activation",
+        ),
+    ]
+}
+
+#[cfg(not(target_os = "macos"))]
+fn check_permissions() -> Vec<DiagnosticCheck> {
+    vec![check(
+        "permission",
+        "Permissions",
+        CheckStatus::Skipped,
+        "Only checked on macOS",
+    )]
+}
+
+/// The store opens and its file, if written yet, is valid JSON.
+fn check_store(app: &AppHandle) -> DiagnosticCheck {
+    let id = "store";
+    let label = "Settings store";
+    if let Err(e) = app.store(STORE_FILE) {
+        return check(
+            id,
+            label,
+            CheckStatus::Fail,
+            format!("Failed to open: {}", e),
+        );
+    }
+
+    let Ok(data_dir) = app.path().app_data_dir() else {
+        return check(
+            id,
+            label,
+            CheckStatus::Warn,
+            "Can't find the data directory",
+        );
+    };
+    let path = data_dir.join(STORE_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(text) => match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(_) => check(id, label, CheckStatus::Pass, "Readable and valid"),
+            Err(e) => check(
+                id,
+                label,
+                CheckStatus::Fail,
+                format!("{} is corrupt: {}", path.display(), e),
+            ),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            check(id, label, CheckStatus::Pass, "Not written yet")
+        }
+        Err(e) => check(
+            id,
+            label,
+            CheckStatus::Fail,
+            format!("Failed to read {}: {}", path.display(), e),
+        ),
+    }
+}
+
+fn check_shortcuts(app: &AppHandle, core: &AppCore) -> DiagnosticCheck {
+    let id = "shortcuts";
+    let label = "Global shortcuts";
+    if !core.settings.read().shortcuts_enabled {
+        return check(id, label, CheckStatus::Skipped, "Shortcuts are turned off");
+    }
+    let missing = shortcuts::unregistered(app);
+    if missing.is_empty() {
+        check(id, label, CheckStatus::Pass, "All registered")
+    } else {
+        check(
+            id,
+            label,
+            CheckStatus::Fail,
+            format!(
+                "Not registered, possibly taken by another app: {}",
+                missing.join(", ")
+            ),
+        )
+    }
+}
+
+fn check_background_tasks(core: &AppCore) -> DiagnosticCheck {
+    let failing: Vec<String> = runtime::task_infos(core)
+        .into_iter()
+        .filter(|task| task.status != TaskStatus::Running)
+        .map(|task| match task.last_error {
+            Some(error) => format!("{} ({})", task.name, error),
+            None => task.name,
+        })
+        .collect();
+    if failing.is_empty() {
+        check(
+            "tasks",
+            "Background tasks",
+            CheckStatus::Pass,
+            "All running",
+        )
+    } else {
+        check(
+            "tasks",
+            "Background tasks",
+            CheckStatus::Warn,
+            format!("Not running: {}", failing.join(", ")),
+        )
+    }
+}
+
+/// Available bytes from `df -Pk` output: the fourth column of the second
+/// line, in 1K blocks.
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let blocks: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(blocks * 1024)
+}
+
+fn disk_status(available: u64) -> CheckStatus {
+    if available < DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if available < DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    }
+}
+
+/// Free space on the volume holding the app's data.
+fn check_disk_space(app: &AppHandle) -> DiagnosticCheck {
+    let id = "disk";
+    let label = "Disk space";
+    let data_dir = app.path().app_data_dir().unwrap_or_default();
+    // The data directory may not exist yet; its volume is what matters
+    let dir = data_dir
+        .ancestors()
+        .find(|dir| dir.exists())
+        .unwrap_or(Path::new("/"));
+
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(dir)
+        .output();
+    let available = match output {
+        Ok(output) if output.status.success() => {
+            parse_df_available(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => None,
+    };
+    match available {
+        Some(bytes) => check(
+            id,
+            label,
+            disk_status(bytes),
+            format!("{:.1} GB free", bytes as f64 / 1e9),
+        ),
+        None => check(id, label, CheckStatus::Skipped, "Couldn't read free space"),
+    }
+}
+
+fn overall_status(checks: &[DiagnosticCheck]) -> CheckStatus {
+    checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(CheckStatus::Skipped)
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Runs every check and returns the report. Provider checks run together,
+/// so the whole run takes at most a few seconds.
+#[tauri::command]
+pub async fn run_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, String> {
+    let provider_checks: Vec<_> = [Provider::Anthropic, Provider::OpenAI, Provider::Google]
+        .into_iter()
+        .map(|provider| {
+            let handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                check_provider(&handle.state::<AppCore>(), provider).await
+            })
+        })
+        .collect();
+    let mut checks = Vec::new();
+    for task in provider_checks {
+        checks.push(
+            task.await
+                .map_err(|e| format!("Failed to check provider: {}", e))?,
+        );
+    }
+
+    let core = app.state::<AppCore>();
+    checks.extend([
+        check(
+            "keychain",
+            "Keychain",
+            CheckStatus::Skipped,
+            "AIThing keeps no Keychain items",
+        ),
+        check(
+            "database",
+            "Database",
+            CheckStatus::Skipped,
+            "AIThing keeps no database",
+        ),
+    ]);
+    checks.extend(check_permissions());
+    checks.push(check_store(&app));
+    checks.push(check_shortcuts(&app, &core));
+    checks.push(check_background_tasks(&core));
+
+    let handle = app.clone();
+    let disk = tauri::async_runtime::spawn_blocking(move || check_disk_space(&handle))
+        .await
+        .map_err(|e| format!("Failed to check disk space: {}", e))?;
+    checks.push(disk);
+
+    Ok(DiagnosticsReport {
+        status: overall_status(&checks),
+        version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        hardware: hardware::hardware_info().clone(),
+        generated_at: chrono::Local::now().to_rfc3339(),
+        checks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn df_output_is_read_in_kilobytes() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/disk3s5 482797652 401234567 81563085 84% /System/Volumes/Data\n";
+
+        assert_eq!(parse_df_available(output), Some(81563085 * 1024));
+        assert_eq!(parse_df_available("Filesystem\n"), None);
+    }
+
+    #[test]
+    fn report_status_is_the_worst_check() {
+        let checks = vec![
+            check("a", "A", CheckStatus::Pass, ""),
+            check("b", "B", CheckStatus::Skipped, ""),
+            check("c", "C", CheckStatus::Warn, ""),
+        ];
+
+        assert_eq!(overall_status(&checks), CheckStatus::Warn);
+        assert_eq!(disk_status(100 * 1024 * 1024), CheckStatus::Fail);
+        assert_eq!(disk_status(500 * 1024 * 1024), CheckStatus::Warn);
+        assert_eq!(disk_status(DISK_WARN_BYTES), CheckStatus::Pass);
+    }
+}
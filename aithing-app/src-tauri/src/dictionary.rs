@@ -0,0 +1,76 @@
+//! Offline dictionary lookups
+//!
+//! Uses the macOS DictionaryServices framework (the same data as Dictionary.app)
+//! so "define: word" queries get an instant answer without a model.
+
+use serde::Serialize;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Definition {
+    pub word: String,
+    pub definition: String,
+}
+
+// =============================================================================
+// MACOS DICTIONARY SERVICES
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use core_foundation::base::{CFRange, CFTypeRef};
+    use core_foundation::string::CFStringRef;
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        pub fn DCSCopyTextDefinition(
+            dictionary: CFTypeRef,
+            text: CFStringRef,
+            range: CFRange,
+        ) -> CFStringRef;
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn copy_definition(word: &str) -> Option<String> {
+    use core_foundation::base::{CFRange, TCFType};
+    use core_foundation::string::CFString;
+
+    let text = CFString::new(word);
+    let range = CFRange::init(0, text.char_len());
+
+    // Passing a null dictionary searches the user's active dictionaries
+    let definition =
+        unsafe { ffi::DCSCopyTextDefinition(std::ptr::null(), text.as_concrete_TypeRef(), range) };
+    if definition.is_null() {
+        return None;
+    }
+
+    let definition = unsafe { CFString::wrap_under_create_rule(definition) };
+    Some(definition.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn copy_definition(_word: &str) -> Option<String> {
+    None
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn lookup_definition(word: String) -> Option<Definition> {
+    let word = word.trim();
+    if word.is_empty() {
+        return None;
+    }
+
+    copy_definition(word).map(|definition| Definition {
+        word: word.to_string(),
+        definition,
+    })
+}
@@ -0,0 +1,153 @@
+//! Declarative configuration file
+//!
+//! Power users can keep settings in `~/.config/aithing/config.toml`, for
+//! example in a dotfiles repository. Keys use the same names as `AppSettings`,
+//! with tables for nested settings (`[quiet_hours]`). Values in the file
+//! override the app's own settings (policy still wins) and are picked up
+//! within seconds of the file changing. A key removed from the file keeps its
+//! last value until it's changed in the app.
+
+use parking_lot::RwLock;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::app_core::AppCore;
+use crate::runtime;
+use crate::AppSettings;
+
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct DotfileState {
+    overrides: RwLock<Option<Map<String, Value>>>,
+}
+
+// =============================================================================
+// LOADING
+// =============================================================================
+
+fn config_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/aithing/config.toml"))
+}
+
+fn read_overrides(path: &Path) -> Result<Option<Map<String, Value>>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let table = toml::from_str::<toml::Table>(&text)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    match serde_json::to_value(table) {
+        Ok(Value::Object(overrides)) => Ok(Some(overrides)),
+        Ok(_) => Ok(None),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+/// Reads the config file into `core`. A file that doesn't parse is reported
+/// and leaves the previous overrides in place.
+pub fn load_dotfile(core: &AppCore) {
+    let Some(path) = config_path() else {
+        return;
+    };
+    match read_overrides(&path) {
+        Ok(overrides) => *core.dotfile.overrides.write() = overrides,
+        Err(e) => eprintln!("{}", e),
+    }
+}
+
+// =============================================================================
+// OVERRIDES
+// =============================================================================
+
+/// Merges `overlay` into `target`, descending into objects so a table only
+/// has to list the keys it changes.
+fn merge(target: &mut Value, overlay: &Value) {
+    match (target, overlay) {
+        (Value::Object(target), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (target, overlay) => *target = overlay.clone(),
+    }
+}
+
+/// Overwrites `settings` with every value the config file sets. A file whose
+/// values don't fit the settings shape is ignored as a whole.
+pub fn apply(core: &AppCore, settings: &mut AppSettings) {
+    let overrides = core.dotfile.overrides.read();
+    let Some(overrides) = overrides.as_ref() else {
+        return;
+    };
+
+    let Ok(mut merged) = serde_json::to_value(&*settings) else {
+        return;
+    };
+    merge(&mut merged, &Value::Object(overrides.clone()));
+
+    match serde_json::from_value::<AppSettings>(merged) {
+        Ok(overridden) => *settings = overridden,
+        Err(e) => eprintln!("Failed to apply config.toml: {}", e),
+    }
+}
+
+/// Reloads the config file whenever it changes and applies it to the running
+/// app, the same way a settings change from the UI is applied.
+pub fn start_dotfile_watcher(app: AppHandle) {
+    let Some(path) = config_path() else {
+        return;
+    };
+    let modified_at = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    runtime::spawn(app, "config-watcher", move |app, shutdown| {
+        let mut last_modified = modified_at(&path);
+
+        while shutdown.sleep(CONFIG_POLL_INTERVAL) {
+            let modified = modified_at(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            let core = app.state::<AppCore>();
+            load_dotfile(&core);
+            let previous = core.settings.read().clone();
+            crate::load_settings_from_store(app, &core);
+            let current = core.settings.read().clone();
+            crate::apply_settings_change(app, &previous, &current);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_override_only_the_keys_they_list() {
+        let core = AppCore::default();
+        let overrides = toml::from_str::<toml::Table>(
+            "shortcuts_enabled = false\n[quiet_hours]\nenabled = true\n",
+        )
+        .unwrap();
+        *core.dotfile.overrides.write() = match serde_json::to_value(overrides).unwrap() {
+            Value::Object(overrides) => Some(overrides),
+            _ => unreachable!(),
+        };
+
+        let mut settings = AppSettings::default();
+        apply(&core, &mut settings);
+
+        assert!(!settings.shortcuts_enabled);
+        assert!(settings.quiet_hours.enabled);
+        assert_eq!(settings.quiet_hours.start, "22:00");
+    }
+}
@@ -0,0 +1,129 @@
+//! Dragging content out of the panel
+//!
+//! Starts a native drag session so responses, images, and exported files can
+//! be dropped straight into Finder, Mail, or any other drop target. Text and
+//! images are written to a temporary file first, since file drags are what
+//! every target accepts.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::response_actions;
+
+/// Drag preview for payloads that aren't images themselves.
+const DRAG_ICON: &[u8] = include_bytes!("../icons/128x128.png");
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DragPayload {
+    /// Response text, dropped as a Markdown file
+    Text { name: String, text: String },
+    /// Raw base64 or a `data:image/...;base64,` URL
+    Image { name: String, data: String },
+    /// Files that already exist on disk
+    Files { paths: Vec<String> },
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Keeps only the final path component so a payload name can't write outside
+/// the drag directory.
+fn sanitize_file_name(name: &str, fallback: &str) -> String {
+    Path::new(name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "..")
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+fn write_drag_file(name: &str, contents: &[u8]) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir()
+        .join("aithing-drag")
+        .join(uuid::Uuid::new_v4().to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create drag folder: {}", e))?;
+
+    let path = dir.join(name);
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write drag file: {}", e))?;
+    Ok(path)
+}
+
+/// Resolves a payload to the files to drag and the preview image to show.
+fn prepare_payload(payload: DragPayload) -> Result<(Vec<PathBuf>, drag::Image), String> {
+    match payload {
+        DragPayload::Text { name, text } => {
+            let mut name = sanitize_file_name(&name, "Response.md");
+            if Path::new(&name).extension().is_none() {
+                name.push_str(".md");
+            }
+            let path = write_drag_file(&name, text.as_bytes())?;
+            Ok((vec![path], drag::Image::Raw(DRAG_ICON.to_vec())))
+        }
+        DragPayload::Image { name, data } => {
+            let bytes = response_actions::decode_image_data(&data)?;
+            let name = sanitize_file_name(&name, "Image.png");
+            let path = write_drag_file(&name, &bytes)?;
+            Ok((vec![path.clone()], drag::Image::File(path)))
+        }
+        DragPayload::Files { paths } => {
+            let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+            if paths.is_empty() {
+                return Err("Nothing to drag".to_string());
+            }
+            if let Some(missing) = paths.iter().find(|path| !path.exists()) {
+                return Err(format!("File not found: {}", missing.display()));
+            }
+            Ok((paths, drag::Image::Raw(DRAG_ICON.to_vec())))
+        }
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Starts dragging `payload` from the main panel. Must be called while the
+/// mouse button is held (from the frontend's `dragstart` handler), since the
+/// native session picks up the current mouse event.
+#[tauri::command]
+pub fn begin_drag(app: AppHandle, payload: DragPayload) -> Result<(), String> {
+    let (paths, image) = prepare_payload(payload)?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    app.run_on_main_thread(move || {
+        if let Err(e) = drag::start_drag(
+            &window,
+            drag::DragItem::Files(paths),
+            image,
+            |_, _| {},
+            drag::Options::default(),
+        ) {
+            eprintln!("Failed to start drag: {}", e);
+        }
+    })
+    .map_err(|e| format!("Failed to start drag: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_cannot_leave_the_drag_folder() {
+        assert_eq!(sanitize_file_name("../x", "fallback"), "x");
+        assert_eq!(sanitize_file_name("/etc/passwd", "fallback"), "passwd");
+        assert_eq!(sanitize_file_name("..", "fallback"), "fallback");
+        assert_eq!(sanitize_file_name("", "fallback"), "fallback");
+        assert_eq!(sanitize_file_name("Notes.md", "fallback"), "Notes.md");
+    }
+}
@@ -0,0 +1,333 @@
+//! Event-tap hotkeys
+//!
+//! Activation gestures the global-shortcut plugin can't express, read from a
+//! listen-only CGEventTap: tapping a modifier key twice on its own (like
+//! double-⌘). The tap only sees modifier changes and key presses, never
+//! consumes them, and is created the first time a gesture is turned on, since
+//! it needs Input Monitoring permission.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "macos")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "macos")]
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(target_os = "macos")]
+use crate::active_app;
+use crate::app_core::AppCore;
+#[cfg(target_os = "macos")]
+use crate::shortcuts::{self, ShortcutAction};
+
+// CGEventFlags
+const FLAG_SHIFT: u64 = 0x0002_0000;
+const FLAG_CONTROL: u64 = 0x0004_0000;
+const FLAG_OPTION: u64 = 0x0008_0000;
+const FLAG_COMMAND: u64 = 0x0010_0000;
+const MODIFIER_FLAGS: u64 = FLAG_SHIFT | FLAG_CONTROL | FLAG_OPTION | FLAG_COMMAND;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modifier {
+    #[default]
+    Command,
+    Control,
+    Option,
+    Shift,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl Modifier {
+    fn flag(self) -> u64 {
+        match self {
+            Modifier::Command => FLAG_COMMAND,
+            Modifier::Control => FLAG_CONTROL,
+            Modifier::Option => FLAG_OPTION,
+            Modifier::Shift => FLAG_SHIFT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DoubleTapSettings {
+    /// Toggle the panel by tapping `modifier` twice
+    pub enabled: bool,
+    pub modifier: Modifier,
+    /// Longest gap between the two taps
+    pub interval_ms: u64,
+}
+
+impl Default for DoubleTapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            modifier: Modifier::Command,
+            interval_ms: 300,
+        }
+    }
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct EventTapState {
+    #[cfg(target_os = "macos")]
+    started: AtomicBool,
+}
+
+/// Recognizes a modifier pressed and released twice on its own. Any other
+/// key or modifier in between, or a slow second tap, starts over.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+struct DoubleTap {
+    /// The modifier is down and nothing else has been pressed with it
+    clean_press: bool,
+    /// When the first clean tap was released
+    first_release: Option<Instant>,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl DoubleTap {
+    /// Feeds a modifier change; returns true when it completes a double tap.
+    fn flags_changed(&mut self, flags: u64, settings: &DoubleTapSettings, now: Instant) -> bool {
+        let target = settings.modifier.flag();
+        let held = flags & MODIFIER_FLAGS;
+
+        if held == target {
+            self.clean_press = true;
+            return false;
+        }
+        if held != 0 {
+            // Another modifier joined in, e.g. Cmd+Shift
+            self.clean_press = false;
+            self.first_release = None;
+            return false;
+        }
+
+        // Everything released
+        if !std::mem::take(&mut self.clean_press) {
+            return false;
+        }
+        let interval = Duration::from_millis(settings.interval_ms);
+        match self.first_release.take() {
+            Some(first) if now.duration_since(first) <= interval => true,
+            _ => {
+                self.first_release = Some(now);
+                false
+            }
+        }
+    }
+
+    /// Any non-modifier key press cancels a tap in progress (Cmd+C isn't a
+    /// tap on Cmd).
+    fn key_down(&mut self) {
+        self.clean_press = false;
+        self.first_release = None;
+    }
+}
+
+// =============================================================================
+// EVENT TAP
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use core_foundation::base::CFTypeRef;
+    use core_foundation::mach_port::CFMachPortRef;
+    use std::ffi::c_void;
+
+    pub const K_CG_SESSION_EVENT_TAP: u32 = 1;
+    pub const K_CG_HEAD_INSERT_EVENT_TAP: u32 = 0;
+    pub const K_CG_EVENT_TAP_OPTION_LISTEN_ONLY: u32 = 1;
+    pub const K_CG_EVENT_KEY_DOWN: u32 = 10;
+    pub const K_CG_EVENT_FLAGS_CHANGED: u32 = 12;
+    pub const K_CG_EVENT_TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFF_FFFE;
+    pub const K_CG_EVENT_TAP_DISABLED_BY_USER_INPUT: u32 = 0xFFFF_FFFF;
+
+    pub type TapCallback = extern "C" fn(
+        proxy: *mut c_void,
+        kind: u32,
+        event: CFTypeRef,
+        info: *mut c_void,
+    ) -> CFTypeRef;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        pub fn CGEventTapCreate(
+            tap: u32,
+            place: u32,
+            options: u32,
+            events_of_interest: u64,
+            callback: TapCallback,
+            info: *mut c_void,
+        ) -> CFMachPortRef;
+        pub fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+        pub fn CGEventGetFlags(event: CFTypeRef) -> u64;
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct TapContext<R: Runtime> {
+    app: AppHandle<R>,
+    port: std::sync::OnceLock<usize>,
+    double_tap: parking_lot::Mutex<DoubleTap>,
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn tap_callback<R: Runtime>(
+    _proxy: *mut std::ffi::c_void,
+    kind: u32,
+    event: core_foundation::base::CFTypeRef,
+    info: *mut std::ffi::c_void,
+) -> core_foundation::base::CFTypeRef {
+    let context = unsafe { &*(info as *const TapContext<R>) };
+
+    match kind {
+        // macOS turns off taps that are slow or after secure input; turn it
+        // back on so the gesture keeps working
+        ffi::K_CG_EVENT_TAP_DISABLED_BY_TIMEOUT | ffi::K_CG_EVENT_TAP_DISABLED_BY_USER_INPUT => {
+            if let Some(port) = context.port.get() {
+                unsafe { ffi::CGEventTapEnable(*port as _, true) };
+            }
+        }
+        ffi::K_CG_EVENT_KEY_DOWN => context.double_tap.lock().key_down(),
+        ffi::K_CG_EVENT_FLAGS_CHANGED => {
+            let core = context.app.state::<AppCore>();
+            let settings = {
+                let settings = core.settings.read();
+                (settings.shortcuts_enabled && settings.double_tap.enabled)
+                    .then_some(settings.double_tap)
+            };
+            let Some(settings) = settings else {
+                return event;
+            };
+
+            let flags = unsafe { ffi::CGEventGetFlags(event) };
+            let completed =
+                context
+                    .double_tap
+                    .lock()
+                    .flags_changed(flags, &settings, Instant::now());
+            if completed && !active_app::is_frontmost_app_excluded(&core) {
+                shortcuts::dispatch(&context.app, &ShortcutAction::ToggleVisibility);
+            }
+        }
+        _ => {}
+    }
+    event
+}
+
+/// Creates the event tap on its own run-loop thread, once. Does nothing
+/// until a gesture is enabled, so users who never turn one on are never
+/// asked for Input Monitoring.
+#[cfg(target_os = "macos")]
+pub fn ensure_started<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    use core_foundation::base::TCFType;
+    use core_foundation::mach_port::CFMachPort;
+    use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop};
+
+    if !core.settings.read().double_tap.enabled {
+        return;
+    }
+    if core.event_tap.started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let context = Box::leak(Box::new(TapContext {
+        app: app.clone(),
+        port: std::sync::OnceLock::new(),
+        double_tap: parking_lot::Mutex::new(DoubleTap::default()),
+    }));
+
+    std::thread::spawn(move || {
+        let mask = (1u64 << ffi::K_CG_EVENT_KEY_DOWN) | (1u64 << ffi::K_CG_EVENT_FLAGS_CHANGED);
+        let port = unsafe {
+            ffi::CGEventTapCreate(
+                ffi::K_CG_SESSION_EVENT_TAP,
+                ffi::K_CG_HEAD_INSERT_EVENT_TAP,
+                ffi::K_CG_EVENT_TAP_OPTION_LISTEN_ONLY,
+                mask,
+                tap_callback::<R>,
+                context as *const TapContext<R> as *mut _,
+            )
+        };
+        if port.is_null() {
+            eprintln!("Failed to create event tap: Input Monitoring permission is required");
+            context
+                .app
+                .state::<AppCore>()
+                .event_tap
+                .started
+                .store(false, Ordering::SeqCst);
+            return;
+        }
+        let _ = context.port.set(port as usize);
+
+        let port = unsafe { CFMachPort::wrap_under_create_rule(port) };
+        let source = match port.create_runloop_source(0) {
+            Ok(source) => source,
+            Err(()) => {
+                eprintln!("Failed to create event tap run loop source");
+                return;
+            }
+        };
+        let run_loop = CFRunLoop::get_current();
+        run_loop.add_source(&source, unsafe { kCFRunLoopCommonModes });
+        unsafe { ffi::CGEventTapEnable(port.as_concrete_TypeRef(), true) };
+        CFRunLoop::run_current();
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn ensure_started<R: tauri::Runtime>(_app: &tauri::AppHandle<R>, _core: &AppCore) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> DoubleTapSettings {
+        DoubleTapSettings {
+            enabled: true,
+            ..DoubleTapSettings::default()
+        }
+    }
+
+    #[test]
+    fn two_quick_clean_taps_complete_the_gesture() {
+        let settings = settings();
+        let start = Instant::now();
+        let mut tap = DoubleTap::default();
+
+        assert!(!tap.flags_changed(FLAG_COMMAND, &settings, start));
+        assert!(!tap.flags_changed(0, &settings, start));
+        assert!(!tap.flags_changed(FLAG_COMMAND, &settings, start));
+        assert!(tap.flags_changed(0, &settings, start + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn slow_taps_and_shortcuts_do_not_count() {
+        let settings = settings();
+        let start = Instant::now();
+        let mut tap = DoubleTap::default();
+
+        tap.flags_changed(FLAG_COMMAND, &settings, start);
+        tap.flags_changed(0, &settings, start);
+        tap.flags_changed(FLAG_COMMAND, &settings, start);
+        assert!(!tap.flags_changed(0, &settings, start + Duration::from_millis(500)));
+
+        // Cmd+C, then a tap on Cmd
+        tap.flags_changed(FLAG_COMMAND, &settings, start);
+        tap.key_down();
+        tap.flags_changed(0, &settings, start);
+        tap.flags_changed(FLAG_COMMAND, &settings, start);
+        assert!(!tap.flags_changed(0, &settings, start + Duration::from_millis(100)));
+    }
+}
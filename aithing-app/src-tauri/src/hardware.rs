@@ -0,0 +1,194 @@
+//! Hardware information
+//!
+//! Reports the Mac's processor, memory, and GPU for settings and support.
+//! Apple Silicon is detected even when the app runs translated under
+//! Rosetta, since that's the case support most needs to spot. Nothing here
+//! changes while the app runs, so it's read once.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HardwareInfo {
+    /// Architecture this build runs as, e.g. `aarch64`
+    pub arch: String,
+    pub apple_silicon: bool,
+    /// Running an Intel build under Rosetta
+    pub translated: bool,
+    pub cpu: Option<String>,
+    pub cpu_cores: usize,
+    pub memory_bytes: Option<u64>,
+    /// CPU and GPU share memory, so all of it is usable by the GPU
+    pub unified_memory: bool,
+    /// Name of the default Metal device; `None` without Metal
+    pub gpu: Option<String>,
+}
+
+static HARDWARE: Lazy<HardwareInfo> = Lazy::new(detect);
+
+// =============================================================================
+// DETECTION
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod sysctl {
+    use std::ffi::{c_char, c_void, CString};
+
+    extern "C" {
+        fn sysctlbyname(
+            name: *const c_char,
+            oldp: *mut c_void,
+            oldlenp: *mut usize,
+            newp: *const c_void,
+            newlen: usize,
+        ) -> i32;
+    }
+
+    /// Reads raw bytes of the sysctl `name`, or `None` if it doesn't exist.
+    fn read(name: &str) -> Option<Vec<u8>> {
+        let name = CString::new(name).ok()?;
+        let mut len = 0;
+        unsafe {
+            if sysctlbyname(
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                &mut len,
+                std::ptr::null(),
+                0,
+            ) != 0
+            {
+                return None;
+            }
+            let mut buffer = vec![0u8; len];
+            if sysctlbyname(
+                name.as_ptr(),
+                buffer.as_mut_ptr().cast(),
+                &mut len,
+                std::ptr::null(),
+                0,
+            ) != 0
+            {
+                return None;
+            }
+            buffer.truncate(len);
+            Some(buffer)
+        }
+    }
+
+    pub fn string(name: &str) -> Option<String> {
+        let bytes = read(name)?;
+        let text = String::from_utf8_lossy(&bytes);
+        Some(text.trim_end_matches('\0').to_string())
+    }
+
+    /// Integer sysctls are 4 or 8 bytes depending on the name.
+    pub fn integer(name: &str) -> Option<u64> {
+        let bytes = read(name)?;
+        match bytes.len() {
+            4 => Some(u32::from_ne_bytes(bytes.try_into().ok()?) as u64),
+            8 => Some(u64::from_ne_bytes(bytes.try_into().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the default Metal device and whether it has unified memory.
+#[cfg(target_os = "macos")]
+fn metal_device() -> Option<(String, bool)> {
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyObject, Bool};
+    use std::ffi::{c_char, CStr};
+
+    #[link(name = "Metal", kind = "framework")]
+    extern "C" {
+        fn MTLCreateSystemDefaultDevice() -> *mut AnyObject;
+    }
+
+    unsafe {
+        // Returned retained; released on drop
+        let device = Retained::from_raw(MTLCreateSystemDefaultDevice())?;
+        let name: *mut AnyObject = msg_send![&*device, name];
+        let utf8: *const c_char = if name.is_null() {
+            std::ptr::null()
+        } else {
+            msg_send![name, UTF8String]
+        };
+        let name = if utf8.is_null() {
+            "Unknown GPU".to_string()
+        } else {
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        };
+        let unified: Bool = msg_send![&*device, hasUnifiedMemory];
+        Some((name, unified.as_bool()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> HardwareInfo {
+    let arch = std::env::consts::ARCH;
+    let translated = sysctl::integer("sysctl.proc_translated") == Some(1);
+    let apple_silicon = arch == "aarch64" || sysctl::integer("hw.optional.arm64") == Some(1);
+    let metal = metal_device();
+
+    HardwareInfo {
+        arch: arch.to_string(),
+        apple_silicon,
+        translated,
+        cpu: sysctl::string("machdep.cpu.brand_string"),
+        cpu_cores: cpu_cores(),
+        memory_bytes: sysctl::integer("hw.memsize"),
+        unified_memory: metal.as_ref().is_some_and(|(_, unified)| *unified),
+        gpu: metal.map(|(name, _)| name),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn detect() -> HardwareInfo {
+    HardwareInfo {
+        arch: std::env::consts::ARCH.to_string(),
+        apple_silicon: false,
+        translated: false,
+        cpu: None,
+        cpu_cores: cpu_cores(),
+        memory_bytes: None,
+        unified_memory: false,
+        gpu: None,
+    }
+}
+
+fn cpu_cores() -> usize {
+    std::thread::available_parallelism().map_or(1, |cores| cores.get())
+}
+
+pub fn hardware_info() -> &'static HardwareInfo {
+    &HARDWARE
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn get_hardware_info() -> HardwareInfo {
+    hardware_info().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardware_is_detected_for_this_build() {
+        let info = get_hardware_info();
+
+        assert_eq!(info.arch, std::env::consts::ARCH);
+        assert!(info.cpu_cores >= 1);
+        // A native Apple Silicon build is never translated
+        assert!(!(info.translated && info.arch == "aarch64"));
+    }
+}
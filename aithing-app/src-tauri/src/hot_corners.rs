@@ -0,0 +1,216 @@
+//! Hot corners
+//!
+//! Pushing the pointer into a screen corner and holding it there briefly
+//! runs the action mapped to that corner, like macOS's own hot corners (a
+//! corner used by both runs both). The pointer is polled a few times a second
+//! rather than watched with an event tap, which would need Input Monitoring
+//! permission; polling stops entirely while no corner has an action.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::active_app;
+use crate::app_core::AppCore;
+use crate::runtime;
+use crate::shortcuts::{self, ShortcutAction};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How often settings are re-checked while every corner is off.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Distance from the corner, in points, that still counts as in it.
+const CORNER_TOLERANCE: f64 = 2.0;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CornerAction {
+    #[default]
+    None,
+    ToggleVisibility,
+    /// Show the panel with the frontmost window attached
+    QuickCapture,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotCorners {
+    pub top_left: CornerAction,
+    pub top_right: CornerAction,
+    pub bottom_left: CornerAction,
+    pub bottom_right: CornerAction,
+    /// How long the pointer has to stay in a corner before its action runs
+    pub dwell_ms: u64,
+}
+
+impl Default for HotCorners {
+    fn default() -> Self {
+        Self {
+            top_left: CornerAction::None,
+            top_right: CornerAction::None,
+            bottom_left: CornerAction::None,
+            bottom_right: CornerAction::None,
+            dwell_ms: 250,
+        }
+    }
+}
+
+impl HotCorners {
+    fn action(&self, corner: Corner) -> CornerAction {
+        match corner {
+            Corner::TopLeft => self.top_left,
+            Corner::TopRight => self.top_right,
+            Corner::BottomLeft => self.bottom_left,
+            Corner::BottomRight => self.bottom_right,
+        }
+    }
+
+    fn any_enabled(&self) -> bool {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_left,
+            self.bottom_right,
+        ]
+        .iter()
+        .any(|action| *action != CornerAction::None)
+    }
+}
+
+// =============================================================================
+// CORNER DETECTION
+// =============================================================================
+
+/// The corner of the display frame (`x`, `y`, `width`, `height`) that
+/// `point` is in, if any. All values are in the same units.
+fn corner_at(point: (f64, f64), frame: (f64, f64, f64, f64), tolerance: f64) -> Option<Corner> {
+    let (px, py) = point;
+    let (x, y, width, height) = frame;
+    if px < x || py < y || px >= x + width || py >= y + height {
+        return None;
+    }
+
+    let left = px - x <= tolerance;
+    let right = x + width - 1.0 - px <= tolerance;
+    let top = py - y <= tolerance;
+    let bottom = y + height - 1.0 - py <= tolerance;
+    match (left, right, top, bottom) {
+        (true, _, true, _) => Some(Corner::TopLeft),
+        (_, true, true, _) => Some(Corner::TopRight),
+        (true, _, _, true) => Some(Corner::BottomLeft),
+        (_, true, _, true) => Some(Corner::BottomRight),
+        _ => None,
+    }
+}
+
+/// The display corner the pointer is in, checked against each display in
+/// its own pixels.
+fn pointer_corner(app: &AppHandle) -> Option<Corner> {
+    let cursor = app.cursor_position().ok()?;
+    let monitors = app.available_monitors().ok()?;
+
+    monitors.iter().find_map(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        corner_at(
+            (cursor.x, cursor.y),
+            (
+                position.x as f64,
+                position.y as f64,
+                size.width as f64,
+                size.height as f64,
+            ),
+            CORNER_TOLERANCE * monitor.scale_factor(),
+        )
+    })
+}
+
+// =============================================================================
+// ACTIONS
+// =============================================================================
+
+fn run_action(app: &AppHandle, action: CornerAction) {
+    // Stay out of excluded apps, as keyboard shortcuts do
+    if active_app::is_frontmost_app_excluded(&app.state::<AppCore>()) {
+        return;
+    }
+
+    let action = match action {
+        CornerAction::None => return,
+        CornerAction::ToggleVisibility => ShortcutAction::ToggleVisibility,
+        CornerAction::QuickCapture => ShortcutAction::QuickCapture,
+    };
+    shortcuts::dispatch(app, &action);
+}
+
+/// Polls the pointer and runs a corner's action once it has dwelt there.
+/// The action runs once per visit; the pointer has to leave the corner
+/// before it can fire again.
+pub fn start_hot_corner_watcher(app: AppHandle) {
+    runtime::spawn(app, "hot-corners", |app, shutdown| {
+        // The corner the pointer is in, when it got there, and whether its
+        // action already ran
+        let mut visit: Option<(Corner, Instant, bool)> = None;
+
+        loop {
+            let corners = app.state::<AppCore>().settings.read().hot_corners.clone();
+            if !corners.any_enabled() {
+                visit = None;
+                if !shutdown.sleep(IDLE_POLL_INTERVAL) {
+                    return;
+                }
+                continue;
+            }
+            if !shutdown.sleep(POLL_INTERVAL) {
+                return;
+            }
+
+            let Some(corner) = pointer_corner(app) else {
+                visit = None;
+                continue;
+            };
+            let (entered, fired) = match visit {
+                Some((current, entered, fired)) if current == corner => (entered, fired),
+                _ => (Instant::now(), false),
+            };
+
+            let dwelt = entered.elapsed() >= Duration::from_millis(corners.dwell_ms);
+            if dwelt && !fired {
+                run_action(app, corners.action(corner));
+            }
+            visit = Some((corner, entered, fired || dwelt));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corners_are_found_within_the_tolerance() {
+        let display = (1440.0, 0.0, 1920.0, 1080.0);
+
+        assert_eq!(
+            corner_at((1440.0, 0.0), display, 2.0),
+            Some(Corner::TopLeft)
+        );
+        assert_eq!(
+            corner_at((3359.0, 1078.0), display, 2.0),
+            Some(Corner::BottomRight)
+        );
+        assert_eq!(corner_at((1450.0, 0.0), display, 2.0), None);
+        assert_eq!(corner_at((100.0, 0.0), display, 2.0), None);
+    }
+}
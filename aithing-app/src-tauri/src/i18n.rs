@@ -0,0 +1,227 @@
+//! Localization
+//!
+//! Strings the backend shows users (native dialogs, errors) come from Fluent
+//! bundles shipped in `locales/`. The locale follows the system unless the
+//! user picks one in settings, and falls back to English, per message, for
+//! anything a bundle doesn't translate.
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Emitter, State};
+use unic_langid::LanguageIdentifier;
+
+use crate::app_core::AppCore;
+
+const FALLBACK_LOCALE: &str = "en-US";
+const BUNDLED_LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleStrings {
+    /// The bundled locale the strings come from
+    pub locale: String,
+    /// Message ID -> text, with English filling any gaps
+    pub messages: BTreeMap<String, String>,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+static RESOURCES: Lazy<Vec<(&'static str, FluentResource)>> = Lazy::new(|| {
+    BUNDLED_LOCALES
+        .iter()
+        .filter_map(
+            |(locale, source)| match FluentResource::try_new(source.to_string()) {
+                Ok(resource) => Some((*locale, resource)),
+                Err((_, errors)) => {
+                    eprintln!("Failed to parse {} strings: {:?}", locale, errors);
+                    None
+                }
+            },
+        )
+        .collect()
+});
+
+// =============================================================================
+// LOCALE SELECTION
+// =============================================================================
+
+/// Picks the bundled locale closest to `requested`: an exact match, then the
+/// same language ("de-AT" -> "de"), then English.
+fn negotiate(requested: &str) -> &'static str {
+    let requested = requested.replace('_', "-");
+    let language = requested.split('-').next().unwrap_or_default();
+
+    BUNDLED_LOCALES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| locale.eq_ignore_ascii_case(&requested))
+        .or_else(|| {
+            BUNDLED_LOCALES
+                .iter()
+                .map(|(locale, _)| *locale)
+                .find(|locale| {
+                    locale
+                        .split('-')
+                        .next()
+                        .is_some_and(|bundled| bundled.eq_ignore_ascii_case(language))
+                })
+        })
+        .unwrap_or(FALLBACK_LOCALE)
+}
+
+fn current_locale(core: &AppCore) -> &'static str {
+    let chosen = core.settings.read().locale.clone();
+    negotiate(
+        &chosen.unwrap_or_else(|| {
+            sys_locale::get_locale().unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+        }),
+    )
+}
+
+// =============================================================================
+// FORMATTING
+// =============================================================================
+
+fn bundle(locale: &str) -> Option<FluentBundle<&'static FluentResource>> {
+    let (_, resource) = RESOURCES.iter().find(|(bundled, _)| *bundled == locale)?;
+    let language = locale.parse::<LanguageIdentifier>().ok()?;
+
+    let mut bundle = FluentBundle::new(vec![language]);
+    // Isolation marks show up as stray characters in native dialogs
+    bundle.set_use_isolating(false);
+    bundle.add_resource(resource).ok()?;
+    Some(bundle)
+}
+
+fn format_message(locale: &str, id: &str, args: &[(&str, &str)]) -> Option<String> {
+    let bundle = bundle(locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.to_string());
+    }
+    let mut errors = Vec::new();
+    Some(
+        bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned(),
+    )
+}
+
+/// Returns message `id` in the current locale with `args` filled in, falling
+/// back to English and then to the ID itself.
+pub fn t(core: &AppCore, id: &str, args: &[(&str, &str)]) -> String {
+    format_message(current_locale(core), id, args)
+        .or_else(|| format_message(FALLBACK_LOCALE, id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn locale_strings(locale: &'static str) -> LocaleStrings {
+    let mut messages = BTreeMap::new();
+    // English first so the locale's own strings overwrite it
+    for bundled in [FALLBACK_LOCALE, locale] {
+        let Some(bundle) = bundle(bundled) else {
+            continue;
+        };
+        let Some((_, resource)) = RESOURCES.iter().find(|(l, _)| *l == bundled) else {
+            continue;
+        };
+        for entry in resource.entries() {
+            let fluent_syntax::ast::Entry::Message(message) = entry else {
+                continue;
+            };
+            let Some(pattern) = bundle
+                .get_message(message.id.name)
+                .and_then(|message| message.value())
+            else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, None, &mut errors);
+            messages.insert(message.id.name.to_string(), text.into_owned());
+        }
+    }
+
+    LocaleStrings {
+        locale: locale.to_string(),
+        messages,
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns the strings for `locale`, or for the current locale when omitted.
+#[tauri::command]
+pub fn get_locale_strings(core: State<'_, AppCore>, locale: Option<String>) -> LocaleStrings {
+    let locale = match locale {
+        Some(locale) => negotiate(&locale),
+        None => current_locale(&core),
+    };
+    locale_strings(locale)
+}
+
+/// Sets the app's locale, or follows the system again when `locale` is
+/// `None`. Emits `locale-changed` with the bundled locale now in use.
+#[tauri::command]
+pub fn set_locale(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    locale: Option<String>,
+) -> Result<String, String> {
+    let locale = locale.map(|locale| locale.trim().to_string());
+    if locale.as_deref() == Some("") {
+        return Err("Locale cannot be empty".to_string());
+    }
+
+    let mut settings = core.settings.read().clone();
+    settings.locale = locale;
+    crate::replace_settings(&app, &core, settings);
+
+    let current = current_locale(&core).to_string();
+    if let Err(e) = crate::voiceover::label_panel(&app, &core) {
+        eprintln!("{}", e);
+    }
+    let _ = app.emit("locale-changed", &current);
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regional_locales_fall_back_to_their_language() {
+        assert_eq!(negotiate("de_AT"), "de");
+        assert_eq!(negotiate("en-GB"), "en-US");
+        assert_eq!(negotiate("ja-JP"), "en-US");
+    }
+
+    #[test]
+    fn messages_are_formatted_in_the_chosen_locale() {
+        let core = AppCore::default();
+        core.settings.write().locale = Some("de".to_string());
+
+        assert_eq!(
+            t(&core, "contact-lookup-title", &[]),
+            "Kontaktsuche erlauben?"
+        );
+        assert_eq!(
+            t(&core, "contact-lookup-message", &[("name", "Ada")]),
+            "AIThing möchte „Ada“ in Ihren Kontakten nachschlagen."
+        );
+        assert_eq!(t(&core, "no-such-message", &[]), "no-such-message");
+    }
+}
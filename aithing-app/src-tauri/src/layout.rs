@@ -0,0 +1,630 @@
+//! Panel geometry
+//!
+//! Sizing and positioning of the main panel: growing it to fit streamed
+//! content while keeping it on the current display, and saving its geometry
+//! so it comes back where it was. Geometry is kept in logical points with the
+//! display's scale factor, so a panel saved on a Retina display isn't
+//! restored at half size on a standard one, or the other way round.
+//!
+//! Geometry is also remembered for each display in each dock mode (docked to
+//! external displays, or on one display alone). When summoned, the panel
+//! goes to that geometry on the display under the pointer, or next to the
+//! pointer or text caret, depending on `summon_placement`.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, Monitor, PhysicalPosition,
+    PhysicalSize, Runtime, State, WebviewWindow, WindowEvent,
+};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::{appearance, WindowState, STORE_FILE};
+
+/// Matches `minHeight` in tauri.conf.json.
+const MIN_CONTENT_HEIGHT: f64 = 400.0;
+/// Gap kept between the panel and the edges of the display's work area.
+const SCREEN_EDGE_MARGIN: f64 = 8.0;
+const RESIZE_ANIMATION_FRAMES: u32 = 10;
+const RESIZE_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+/// Space between the summon point (pointer or caret) and the panel's top.
+const SUMMON_ANCHOR_GAP: f64 = 12.0;
+const WINDOW_STATE_KEY: &str = "window_state";
+const DISPLAY_GEOMETRY_KEY: &str = "display_geometry";
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct LayoutState {
+    /// Bumped on every resize so an in-flight animation stops when a newer
+    /// one starts (e.g. several resizes while a response streams in).
+    resize_generation: AtomicU64,
+    /// Last geometry on each display, keyed by `display_key`
+    display_frames: RwLock<HashMap<String, Frame>>,
+}
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummonPlacement {
+    /// Where the panel was last used on the display under the pointer
+    #[default]
+    ActiveDisplay,
+    /// Just below the mouse pointer
+    Cursor,
+    /// Just below the text caret, falling back to the pointer
+    Caret,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DisplayScaleChange {
+    pub previous: f64,
+    pub current: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Frame {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+// =============================================================================
+// ANIMATION
+// =============================================================================
+
+fn ease_out_cubic(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Animates the window from `from` to `to`, or moves it there in one step
+/// when Reduce Motion is on.
+fn animate_frame(core: &AppCore, window: WebviewWindow, from: Frame, to: Frame) {
+    let generation = core.layout.resize_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    if appearance::read_accessibility_prefs().reduce_motion {
+        let _ = window.set_size(LogicalSize::new(to.width, to.height));
+        let _ = window.set_position(LogicalPosition::new(to.x, to.y));
+        return;
+    }
+
+    let frame_delay = RESIZE_ANIMATION_DURATION / RESIZE_ANIMATION_FRAMES;
+
+    std::thread::spawn(move || {
+        let core = window.state::<AppCore>();
+        for step in 1..=RESIZE_ANIMATION_FRAMES {
+            if core.layout.resize_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            let t = ease_out_cubic(step as f64 / RESIZE_ANIMATION_FRAMES as f64);
+            let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+            let _ = window.set_size(LogicalSize::new(
+                lerp(from.width, to.width),
+                lerp(from.height, to.height),
+            ));
+            let _ =
+                window.set_position(LogicalPosition::new(lerp(from.x, to.x), lerp(from.y, to.y)));
+
+            std::thread::sleep(frame_delay);
+        }
+    });
+}
+
+// =============================================================================
+// PERSISTENCE
+// =============================================================================
+
+/// Identifies a display in a dock mode. Displays are told apart by name and
+/// resolution, since macOS doesn't hand out stable IDs through Tauri; the
+/// mode is "docked" whenever more than one display is connected.
+fn display_key(name: Option<&str>, width: u32, height: u32, display_count: usize) -> String {
+    let mode = if display_count > 1 {
+        "docked"
+    } else {
+        "undocked"
+    };
+    format!(
+        "{}@{}x{}/{}",
+        name.unwrap_or("display"),
+        width,
+        height,
+        mode
+    )
+}
+
+fn monitor_key(monitor: &Monitor, display_count: usize) -> String {
+    let size = monitor.size();
+    display_key(
+        monitor.name().map(String::as_str),
+        size.width,
+        size.height,
+        display_count,
+    )
+}
+
+/// Records the panel's current geometry for the display it's on.
+fn remember_display_frame<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let (Ok(monitors), Ok(Some(monitor))) = (window.available_monitors(), window.current_monitor())
+    else {
+        return;
+    };
+
+    let window_state = core.window.read();
+    let frame = Frame {
+        x: window_state.x,
+        y: window_state.y,
+        width: window_state.width,
+        height: window_state.height,
+    };
+    core.layout
+        .display_frames
+        .write()
+        .insert(monitor_key(&monitor, monitors.len()), frame);
+}
+
+pub fn save_window_state<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    remember_display_frame(app, core);
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Ok(json) = serde_json::to_value(&*core.window.read()) {
+            store.set(WINDOW_STATE_KEY, json);
+        }
+        if let Ok(json) = serde_json::to_value(&*core.layout.display_frames.read()) {
+            store.set(DISPLAY_GEOMETRY_KEY, json);
+        }
+        let _ = store.save();
+    }
+}
+
+/// Loads saved geometry into `core`. Returns false when nothing was saved,
+/// so the panel keeps the position from tauri.conf.json.
+pub fn load_window_state<R: Runtime>(app: &AppHandle<R>, core: &AppCore) -> bool {
+    let Ok(store) = app.store(STORE_FILE) else {
+        return false;
+    };
+    if let Some(frames) = store
+        .get(DISPLAY_GEOMETRY_KEY)
+        .and_then(|json| serde_json::from_value(json).ok())
+    {
+        *core.layout.display_frames.write() = frames;
+    }
+
+    let Some(saved) = store
+        .get(WINDOW_STATE_KEY)
+        .and_then(|json| serde_json::from_value::<WindowState>(json).ok())
+    else {
+        return false;
+    };
+    *core.window.write() = saved;
+    true
+}
+
+/// The display whose work area contains the logical point (`x`, `y`). Each
+/// display's frame is converted with its own scale factor.
+fn monitor_at(monitors: &[Monitor], x: f64, y: f64) -> Option<&Monitor> {
+    monitors.iter().find(|monitor| {
+        let scale = monitor.scale_factor();
+        let area = monitor.work_area();
+        let left = area.position.x as f64 / scale;
+        let top = area.position.y as f64 / scale;
+        let right = left + area.size.width as f64 / scale;
+        let bottom = top + area.size.height as f64 / scale;
+        (left..right).contains(&x) && (top..bottom).contains(&y)
+    })
+}
+
+/// Moves the panel to its saved geometry, converting points to pixels with
+/// the scale of the display it lands on rather than the one it starts on.
+/// A panel saved on a display that's gone is moved to the primary display.
+/// Emits `display-scale-changed` when that display's scaling differs from
+/// the saved one.
+pub fn restore_window_geometry(app: &AppHandle, core: &AppCore) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list displays: {}", e))?;
+
+    let saved = core.window.read().clone();
+    let (monitor, x, y) = match monitor_at(&monitors, saved.x, saved.y) {
+        Some(monitor) => (monitor.clone(), saved.x, saved.y),
+        None => {
+            let primary = window
+                .primary_monitor()
+                .map_err(|e| format!("Failed to get primary display: {}", e))?
+                .ok_or("Failed to get primary display")?;
+            let scale = primary.scale_factor();
+            let area = primary.work_area();
+            let x = area.position.x as f64 / scale + SCREEN_EDGE_MARGIN;
+            let y = area.position.y as f64 / scale + SCREEN_EDGE_MARGIN;
+            (primary, x, y)
+        }
+    };
+
+    let scale = monitor.scale_factor();
+    window
+        .set_position(PhysicalPosition::new(x * scale, y * scale))
+        .map_err(|e| format!("Failed to move window: {}", e))?;
+    window
+        .set_size(PhysicalSize::new(saved.width * scale, saved.height * scale))
+        .map_err(|e| format!("Failed to resize window: {}", e))?;
+
+    {
+        let mut window_state = core.window.write();
+        window_state.x = x;
+        window_state.y = y;
+        window_state.scale_factor = scale;
+    }
+    if saved.scale_factor != scale {
+        let _ = app.emit(
+            "display-scale-changed",
+            DisplayScaleChange {
+                previous: saved.scale_factor,
+                current: scale,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Logical work area of `monitor`.
+fn work_area(monitor: &Monitor) -> Frame {
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    Frame {
+        x: area.position.x as f64 / scale,
+        y: area.position.y as f64 / scale,
+        width: area.size.width as f64 / scale,
+        height: area.size.height as f64 / scale,
+    }
+}
+
+/// Moves `frame` so it fits inside `area`, keeping its size unless it's
+/// larger than the area.
+fn clamp_to_area(frame: Frame, area: Frame) -> Frame {
+    let width = frame.width.min(area.width - 2.0 * SCREEN_EDGE_MARGIN);
+    let height = frame.height.min(area.height - 2.0 * SCREEN_EDGE_MARGIN);
+    let min_x = area.x + SCREEN_EDGE_MARGIN;
+    let min_y = area.y + SCREEN_EDGE_MARGIN;
+    Frame {
+        x: frame.x.clamp(
+            min_x,
+            (area.x + area.width - SCREEN_EDGE_MARGIN - width).max(min_x),
+        ),
+        y: frame.y.clamp(
+            min_y,
+            (area.y + area.height - SCREEN_EDGE_MARGIN - height).max(min_y),
+        ),
+        width,
+        height,
+    }
+}
+
+/// The panel's frame for a summon with `placement`. `anchor` is the pointer
+/// or caret position in points; `saved` is the frame last used on the
+/// display.
+fn summon_frame(
+    placement: SummonPlacement,
+    current: Frame,
+    saved: Option<Frame>,
+    anchor: Option<(f64, f64)>,
+    area: Frame,
+) -> Frame {
+    let frame = match (placement, anchor) {
+        (SummonPlacement::Cursor | SummonPlacement::Caret, Some((x, y))) => Frame {
+            x: x - current.width / 2.0,
+            y: y + SUMMON_ANCHOR_GAP,
+            ..current
+        },
+        _ => saved.unwrap_or(Frame {
+            x: area.x + (area.width - current.width) / 2.0,
+            y: area.y + (area.height - current.height) / 2.0,
+            ..current
+        }),
+    };
+    clamp_to_area(frame, area)
+}
+
+/// Positions the panel for `summon_placement` before it's shown, on the
+/// display under the pointer:
+/// - `ActiveDisplay`: the geometry last used on that display in the current
+///   dock mode, or centered when there's none
+/// - `Cursor`: just below the pointer
+/// - `Caret`: just below the text caret in the focused app, or below the
+///   pointer when the app doesn't report a caret
+///
+/// The panel is always kept inside the display's work area.
+pub fn place_for_summon<R: Runtime>(app: &AppHandle<R>, core: &AppCore) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let monitors = window
+        .available_monitors()
+        .map_err(|e| format!("Failed to list displays: {}", e))?;
+    let cursor = app
+        .cursor_position()
+        .map_err(|e| format!("Failed to get pointer position: {}", e))?;
+
+    let Some(monitor) = monitors.iter().find(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        let (x, y) = (cursor.x as i32, cursor.y as i32);
+        x >= position.x
+            && y >= position.y
+            && x < position.x + size.width as i32
+            && y < position.y + size.height as i32
+    }) else {
+        return Ok(());
+    };
+    let scale = monitor.scale_factor();
+
+    let placement = core.settings.read().summon_placement;
+    let pointer = (cursor.x / scale, cursor.y / scale);
+    let anchor = match placement {
+        SummonPlacement::ActiveDisplay => None,
+        SummonPlacement::Cursor => Some(pointer),
+        SummonPlacement::Caret => Some(
+            caret_anchor()
+                .filter(|(x, y)| monitor_at(&monitors, *x, *y).is_some())
+                .unwrap_or(pointer),
+        ),
+    };
+    let saved = core
+        .layout
+        .display_frames
+        .read()
+        .get(&monitor_key(monitor, monitors.len()))
+        .copied();
+    let current = {
+        let window_state = core.window.read();
+        Frame {
+            x: window_state.x,
+            y: window_state.y,
+            width: window_state.width,
+            height: window_state.height,
+        }
+    };
+    let frame = summon_frame(placement, current, saved, anchor, work_area(monitor));
+
+    // Each change costs the webview a relayout before it's on screen, so
+    // leave alone whatever already matches
+    if (frame.x, frame.y) != (current.x, current.y) {
+        window
+            .set_position(PhysicalPosition::new(frame.x * scale, frame.y * scale))
+            .map_err(|e| format!("Failed to move window: {}", e))?;
+    }
+    if (frame.width, frame.height) != (current.width, current.height) {
+        window
+            .set_size(PhysicalSize::new(frame.width * scale, frame.height * scale))
+            .map_err(|e| format!("Failed to resize window: {}", e))?;
+    }
+
+    let mut window_state = core.window.write();
+    window_state.x = frame.x;
+    window_state.y = frame.y;
+    window_state.width = frame.width;
+    window_state.height = frame.height;
+    window_state.scale_factor = scale;
+    Ok(())
+}
+
+/// Bottom center of the text caret in the focused app, in points.
+#[cfg(target_os = "macos")]
+fn caret_anchor() -> Option<(f64, f64)> {
+    let caret = crate::accessibility::focused_caret_bounds()?;
+    Some((caret.x + caret.width / 2.0, caret.y + caret.height))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn caret_anchor() -> Option<(f64, f64)> {
+    None
+}
+
+/// Keeps `AppCore`'s window state in step with the panel as it's moved,
+/// resized, or dragged to a display with different scaling. A scale change
+/// is saved straight away and emitted as `display-scale-changed`.
+pub fn track_window_geometry(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let handle = app.clone();
+    let tracked = window.clone();
+
+    window.on_window_event(move |event| {
+        let core = handle.state::<AppCore>();
+        let scale = tracked
+            .scale_factor()
+            .unwrap_or(core.window.read().scale_factor);
+        match event {
+            WindowEvent::Moved(position) => {
+                let position = position.to_logical::<f64>(scale);
+                let mut window_state = core.window.write();
+                window_state.x = position.x;
+                window_state.y = position.y;
+            }
+            WindowEvent::Resized(size) => {
+                let size = size.to_logical::<f64>(scale);
+                let mut window_state = core.window.write();
+                window_state.width = size.width;
+                window_state.height = size.height;
+            }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                let previous =
+                    std::mem::replace(&mut core.window.write().scale_factor, *scale_factor);
+                save_window_state(&handle, &core);
+                let _ = handle.emit(
+                    "display-scale-changed",
+                    DisplayScaleChange {
+                        previous,
+                        current: *scale_factor,
+                    },
+                );
+            }
+            _ => {}
+        }
+    });
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Animates the panel to `height` (in logical points), clamped between the
+/// minimum panel height and the display's work area. The panel grows
+/// downwards from its top edge and only moves up when its bottom edge would
+/// leave the display. The frame is saved with the rest of the window state
+/// when the panel hides, not on every resize. Returns the height that was
+/// applied.
+#[tauri::command]
+pub fn resize_to_content(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    height: f64,
+) -> Result<f64, String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| format!("Failed to get current monitor: {}", e))?
+        .ok_or("Failed to get current monitor")?;
+
+    let scale = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    let area_top = work_area.position.y as f64 / scale;
+    let area_bottom = area_top + work_area.size.height as f64 / scale;
+
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {}", e))?
+        .to_logical::<f64>(scale);
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {}", e))?
+        .to_logical::<f64>(scale);
+
+    let max_height = (area_bottom - area_top - 2.0 * SCREEN_EDGE_MARGIN).max(MIN_CONTENT_HEIGHT);
+    let target_height = height.clamp(MIN_CONTENT_HEIGHT, max_height);
+
+    let max_bottom = area_bottom - SCREEN_EDGE_MARGIN;
+    let target_y = if position.y + target_height > max_bottom {
+        (max_bottom - target_height).max(area_top + SCREEN_EDGE_MARGIN)
+    } else {
+        position.y
+    };
+
+    let from = Frame {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+    let to = Frame {
+        y: target_y,
+        height: target_height,
+        ..from
+    };
+    animate_frame(&core, window, from, to);
+
+    {
+        let mut window_state = core.window.write();
+        window_state.x = to.x;
+        window_state.y = to.y;
+        window_state.width = to.width;
+        window_state.height = to.height;
+        window_state.scale_factor = scale;
+    }
+
+    Ok(target_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Harness;
+
+    #[test]
+    fn window_state_survives_a_restart_with_its_scale() {
+        let harness = Harness::new();
+
+        let app = harness.launch();
+        let core = app.state::<AppCore>();
+        assert!(!load_window_state(app.handle(), &core));
+        *core.window.write() = WindowState {
+            width: 720.0,
+            x: 1600.0,
+            scale_factor: 2.0,
+            ..WindowState::default()
+        };
+        save_window_state(app.handle(), &core);
+
+        let app = harness.launch();
+        let core = app.state::<AppCore>();
+        assert!(load_window_state(app.handle(), &core));
+        let window_state = core.window.read();
+        assert_eq!(window_state.width, 720.0);
+        assert_eq!(window_state.x, 1600.0);
+        assert_eq!(window_state.scale_factor, 2.0);
+    }
+
+    #[test]
+    fn summons_near_the_pointer_stay_on_the_display() {
+        let area = Frame {
+            x: 0.0,
+            y: 25.0,
+            width: 1440.0,
+            height: 875.0,
+        };
+        let current = Frame {
+            x: 100.0,
+            y: 100.0,
+            width: 660.0,
+            height: 600.0,
+        };
+
+        let near_pointer = summon_frame(
+            SummonPlacement::Cursor,
+            current,
+            None,
+            Some((720.0, 100.0)),
+            area,
+        );
+        assert_eq!((near_pointer.x, near_pointer.y), (390.0, 112.0));
+
+        // Pointer in the bottom-right corner: the panel is pushed back inside
+        let in_corner = summon_frame(
+            SummonPlacement::Cursor,
+            current,
+            None,
+            Some((1430.0, 890.0)),
+            area,
+        );
+        assert_eq!((in_corner.x, in_corner.y), (772.0, 292.0));
+
+        let centered = summon_frame(SummonPlacement::ActiveDisplay, current, None, None, area);
+        assert_eq!((centered.x, centered.y), (390.0, 162.5));
+    }
+
+    #[test]
+    fn display_keys_tell_dock_modes_apart() {
+        let laptop = display_key(Some("Built-in Retina Display"), 3024, 1964, 1);
+        let docked = display_key(Some("Built-in Retina Display"), 3024, 1964, 2);
+
+        assert_eq!(laptop, "Built-in Retina Display@3024x1964/undocked");
+        assert_ne!(laptop, docked);
+    }
+}
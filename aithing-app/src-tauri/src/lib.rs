@@ -2,26 +2,117 @@
 //!
 //! This module contains the main backend logic for the AIThing application:
 //! - macOS window management with NSPanel for fullscreen overlay
-//! - Global keyboard shortcuts
+//! - Panel behavior under Spaces, Stage Manager, and Mission Control
+//! - Panel sizing that follows content height
+//! - Panel geometry saved in logical points and remembered per display
+//! - Detachable conversation windows
+//! - Persistent user memories
+//! - Scheduled quiet hours
+//! - Global keyboard shortcuts and leader-key chords, suppressed in excluded apps
+//! - Hot corners mapped to panel actions
+//! - Double-tap modifier activation through an event tap
+//! - Activation latency timings against a 100 ms budget
+//! - Memory trimming and page unloading while the panel is hidden
+//! - Supervised background tasks with restart on panic and shutdown on exit
+//! - App Nap held off while responses stream into a hidden panel
+//! - Display sleep prevention during long tasks
+//! - Graceful quit and relaunch that drain in-flight generations, and factory reset
+//! - Support diagnostics covering providers, permissions, storage, and shortcuts
+//! - Processor, memory, and Metal GPU detection
 //! - Tauri commands for frontend interaction
+//! - Backend state held in one Tauri-managed `AppCore`
+//! - Instant local answers (calculator, unit conversion, dictionary)
+//! - Context menu actions on links, images, and code in responses
+//! - Markdown rendering with syntax-highlighted code blocks
+//! - Backend-managed themes with native window materials
+//! - System light/dark appearance and accent color tracking
+//! - Accessibility display preferences (motion, transparency, contrast, text size)
+//! - AppleScript bridge for Mail, Calendar, and Reminders
+//! - Active browser tab context
+//! - Approval-gated contact lookup
+//! - Localized backend strings from bundled Fluent files
+//! - Frontmost window capture for visual context
+//! - Dragging responses, images, and files out of the panel
+//! - Quick Look previews of attachments and generated files
+//! - Admin-managed policy enforced over user settings
+//! - Named configuration profiles
+//! - Optional `config.toml` that overrides stored settings
+//! - Configuration export and import, with optionally encrypted API keys
+//! - Provider API key validation and live model catalogs
+//! - Provider keys and base URLs from the environment or allowed `.env` files
+//! - Model capability registry for feature gating
+//! - Mock provider that streams canned responses for development
+//! - Recording and replay of provider traffic for debugging
+//! - VoiceOver labels for the panel and spoken response announcements
+
+#[cfg(target_os = "macos")]
+mod accessibility;
+mod activation;
+mod active_app;
+mod app_core;
+mod app_nap;
+mod appearance;
+mod applescript;
+mod browser_context;
+mod calc;
+mod capabilities;
+mod contacts;
+mod diagnostics;
+mod dictionary;
+mod dotfile;
+mod drag_out;
+mod event_tap;
+mod hardware;
+mod hot_corners;
+mod i18n;
+mod layout;
+mod lifecycle;
+mod markdown;
+mod memory;
+mod mock_provider;
+mod panel_behavior;
+mod policy;
+mod portable_config;
+mod profiles;
+mod providers;
+mod quick_look;
+mod quiet_hours;
+mod response_actions;
+mod runtime;
+mod secrets;
+mod shortcuts;
+mod sleep_prevention;
+mod suspend;
+#[cfg(target_os = "macos")]
+mod system_prefs;
+#[cfg(test)]
+mod test_support;
+mod theme;
+mod vcr;
+mod voiceover;
+mod window_capture;
+mod windows;
 
-use once_cell::sync::Lazy;
-use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::path::Path;
+use std::time::Duration;
 #[cfg(target_os = "macos")]
 use tauri::WebviewWindow;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 #[cfg(target_os = "macos")]
-use tauri_nspanel::{tauri_panel, CollectionBehavior, PanelLevel, StyleMask, WebviewWindowExt};
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
+use tauri_nspanel::{tauri_panel, PanelLevel, StyleMask, WebviewWindowExt};
 use tauri_plugin_store::StoreExt;
 
+use app_core::AppCore;
+
 // =============================================================================
 // DATA TYPES
 // =============================================================================
 
+/// Panel geometry in logical points, which stay the same size on screen
+/// whatever the display's scaling.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WindowState {
     pub is_visible: bool,
     pub is_expanded: bool,
@@ -29,13 +120,66 @@ pub struct WindowState {
     pub height: f64,
     pub x: f64,
     pub y: f64,
+    /// Backing scale factor of the display the panel was last on
+    pub scale_factor: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            is_visible: true,
+            is_expanded: false,
+            width: 660.0,
+            height: 600.0,
+            x: 0.0,
+            y: 0.0,
+            scale_factor: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub show_in_screenshot: bool,
     pub open_at_login: bool,
     pub shortcuts_enabled: bool,
+    /// Bundle IDs of apps where shortcuts and context capture are disabled
+    pub excluded_apps: Vec<String>,
+    pub quiet_hours: quiet_hours::QuietHours,
+    /// Lets drafting actions look up contacts; each lookup is still confirmed
+    pub contacts_lookup_enabled: bool,
+    /// Fetch exchange rates to answer currency conversions in the calculator
+    pub currency_rates_enabled: bool,
+    /// Attach an image of the frontmost window to each message sent to a model
+    pub include_active_window: bool,
+    /// Default format for copied responses
+    pub copy_format: response_actions::CopyFormat,
+    /// Locale for backend strings, e.g. "de"; `None` follows the system
+    pub locale: Option<String>,
+    /// Absolute paths of `.env` files allowed to supply provider credentials
+    pub env_files: Vec<String>,
+    /// Timing, failures, and script for the `mock` provider
+    pub mock_provider: mock_provider::MockProviderSettings,
+    /// Have VoiceOver announce each finished response
+    pub accessibility_announcements: bool,
+    /// Smallest font size, in points, for rendered responses; `None` keeps
+    /// the stylesheet's sizes
+    pub markdown_min_font_size: Option<f64>,
+    /// How the panel opts out of stages while Stage Manager is on
+    pub stage_manager_behavior: panel_behavior::StageManagerBehavior,
+    /// Leave the panel out of Mission Control and window cycling
+    pub exclude_from_mission_control: bool,
+    /// Where the panel appears when it's shown
+    pub summon_placement: layout::SummonPlacement,
+    /// Actions for screen corners
+    pub hot_corners: hot_corners::HotCorners,
+    /// Toggle the panel by double-tapping a modifier key
+    pub double_tap: event_tap::DoubleTapSettings,
+    /// Two-step shortcuts: a leader followed by a second key
+    pub chords: shortcuts::ChordSettings,
+    /// What's released while the panel is hidden
+    pub suspend: suspend::SuspendSettings,
 }
 
 impl Default for AppSettings {
@@ -44,35 +188,47 @@ impl Default for AppSettings {
             show_in_screenshot: false,
             open_at_login: false,
             shortcuts_enabled: true,
+            excluded_apps: vec![
+                "com.1password.1password".to_string(),
+                "com.agilebits.onepassword7".to_string(),
+                "com.bitwarden.desktop".to_string(),
+                "com.apple.keychainaccess".to_string(),
+                "com.apple.Passwords".to_string(),
+            ],
+            quiet_hours: quiet_hours::QuietHours::default(),
+            contacts_lookup_enabled: false,
+            currency_rates_enabled: false,
+            include_active_window: false,
+            copy_format: response_actions::CopyFormat::default(),
+            locale: None,
+            env_files: Vec::new(),
+            mock_provider: mock_provider::MockProviderSettings::default(),
+            accessibility_announcements: false,
+            markdown_min_font_size: None,
+            stage_manager_behavior: panel_behavior::StageManagerBehavior::default(),
+            exclude_from_mission_control: false,
+            summon_placement: layout::SummonPlacement::default(),
+            hot_corners: hot_corners::HotCorners::default(),
+            double_tap: event_tap::DoubleTapSettings::default(),
+            chords: shortcuts::ChordSettings::default(),
+            suspend: suspend::SuspendSettings::default(),
         }
     }
 }
 
-// =============================================================================
-// GLOBAL STATE
-// =============================================================================
-
-static APP_HANDLE: Lazy<Arc<RwLock<Option<AppHandle>>>> = Lazy::new(|| Arc::new(RwLock::new(None)));
-static WINDOW_STATE: Lazy<Arc<RwLock<WindowState>>> = Lazy::new(|| {
-    Arc::new(RwLock::new(WindowState {
-        is_visible: true,
-        is_expanded: false,
-        width: 660.0,
-        height: 600.0,
-        x: 0.0,
-        y: 0.0,
-    }))
-});
-static APP_SETTINGS: Lazy<Arc<RwLock<AppSettings>>> =
-    Lazy::new(|| Arc::new(RwLock::new(AppSettings::default())));
-
 // =============================================================================
 // SETTINGS STORAGE
 // =============================================================================
 
-fn save_settings_to_store(app: &AppHandle) {
-    if let Ok(store) = app.store("aithing-store.json") {
-        let settings = APP_SETTINGS.read();
+pub(crate) const STORE_FILE: &str = "aithing-store.json";
+/// How often the store file is checked for edits made outside the app.
+const STORE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How long exit waits for background tasks to stop.
+const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn save_settings_to_store<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        let settings = core.settings.read();
         if let Ok(json) = serde_json::to_value(&*settings) {
             store.set("settings", json);
             let _ = store.save();
@@ -80,15 +236,111 @@ fn save_settings_to_store(app: &AppHandle) {
     }
 }
 
-fn load_settings_from_store(app: &AppHandle) {
-    if let Ok(store) = app.store("aithing-store.json") {
+fn load_settings_from_store<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
         if let Some(settings_json) = store.get("settings") {
             if let Ok(settings) = serde_json::from_value::<AppSettings>(settings_json.clone()) {
-                let mut app_settings = APP_SETTINGS.write();
+                let mut app_settings = core.settings.write();
                 *app_settings = settings;
             }
         }
     }
+    apply_overrides(core, &mut core.settings.write());
+}
+
+/// Layers config.toml and then policy over `settings`, so policy always wins.
+fn apply_overrides(core: &AppCore, settings: &mut AppSettings) {
+    dotfile::apply(core, settings);
+    policy::enforce(core, settings);
+}
+
+/// Replaces all settings in one step, with overrides applied on top, persists
+/// them, and applies whatever changed.
+fn replace_settings<R: Runtime>(app: &AppHandle<R>, core: &AppCore, settings: AppSettings) {
+    let previous = core.settings.read().clone();
+    let current = {
+        let mut app_settings = core.settings.write();
+        *app_settings = settings;
+        apply_overrides(core, &mut app_settings);
+        app_settings.clone()
+    };
+    save_settings_to_store(app, core);
+    apply_settings_change(app, &previous, &current);
+}
+
+/// Reconfigures running subsystems for the settings that changed and emits
+/// `settings-changed`, so no setting needs a restart to take effect.
+fn apply_settings_change<R: Runtime>(
+    app: &AppHandle<R>,
+    previous: &AppSettings,
+    current: &AppSettings,
+) {
+    if previous == current {
+        return;
+    }
+
+    if previous.shortcuts_enabled != current.shortcuts_enabled || previous.chords != current.chords
+    {
+        if let Err(e) = shortcuts::set_global_shortcuts(app, current.shortcuts_enabled) {
+            eprintln!("{}", e);
+        }
+    }
+    if previous.stage_manager_behavior != current.stage_manager_behavior
+        || previous.exclude_from_mission_control != current.exclude_from_mission_control
+    {
+        if let Err(e) = panel_behavior::apply(app, &app.state::<AppCore>()) {
+            eprintln!("{}", e);
+        }
+    }
+    if current.double_tap.enabled && !previous.double_tap.enabled {
+        event_tap::ensure_started(app, &app.state::<AppCore>());
+    }
+    if previous.show_in_screenshot != current.show_in_screenshot {
+        if let Err(e) = set_content_protection(app, !current.show_in_screenshot) {
+            eprintln!("{}", e);
+        }
+    }
+
+    let _ = app.emit("settings-changed", current);
+}
+
+/// Picks up edits made to the store file outside the app (by hand or by a
+/// sync tool) and applies them the way `set_settings` would.
+fn start_settings_watcher(app: AppHandle) {
+    let path = match app.path().app_data_dir() {
+        Ok(dir) => dir.join(STORE_FILE),
+        Err(e) => {
+            eprintln!("Failed to locate settings store: {}", e);
+            return;
+        }
+    };
+    let modified_at = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    runtime::spawn(app, "settings-watcher", move |app, shutdown| {
+        let mut last_modified = modified_at(&path);
+
+        while shutdown.sleep(STORE_POLL_INTERVAL) {
+            let modified = modified_at(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            // Our own saves land here too; they reload to identical settings
+            let Ok(store) = app.store(STORE_FILE) else {
+                continue;
+            };
+            if let Err(e) = store.reload() {
+                eprintln!("Failed to reload settings store: {}", e);
+                continue;
+            }
+            let core = app.state::<AppCore>();
+            let previous = core.settings.read().clone();
+            load_settings_from_store(app, &core);
+            let current = core.settings.read().clone();
+            apply_settings_change(app, &previous, &current);
+        }
+    });
 }
 
 // =============================================================================
@@ -96,43 +348,39 @@ fn load_settings_from_store(app: &AppHandle) {
 // =============================================================================
 
 #[tauri::command]
-fn get_window_state() -> WindowState {
-    WINDOW_STATE.read().clone()
+fn get_window_state(core: State<'_, AppCore>) -> WindowState {
+    core.window.read().clone()
 }
 
 #[tauri::command]
-fn set_window_state(state: WindowState) {
-    let mut window_state = WINDOW_STATE.write();
-    *window_state = state;
+fn set_window_state(app: AppHandle, core: State<'_, AppCore>, state: WindowState) {
+    *core.window.write() = state;
+    layout::save_window_state(&app, &core);
 }
 
 #[tauri::command]
-fn get_settings() -> AppSettings {
-    APP_SETTINGS.read().clone()
+fn get_settings(core: State<'_, AppCore>) -> AppSettings {
+    core.settings.read().clone()
 }
 
 #[tauri::command]
-fn set_settings(app: AppHandle, settings: AppSettings) {
-    {
-        let mut app_settings = APP_SETTINGS.write();
-        *app_settings = settings;
-    }
-    save_settings_to_store(&app);
+fn set_settings(app: AppHandle, core: State<'_, AppCore>, settings: AppSettings) {
+    replace_settings(&app, &core, settings);
 }
 
 #[tauri::command]
 fn set_screenshot_protection(app: AppHandle, enabled: bool) -> Result<(), String> {
-    let window = app
-        .get_webview_window("main")
-        .ok_or("Failed to get main window")?;
-    window
-        .set_content_protected(enabled)
-        .map_err(|e| format!("Failed to update content protection: {}", e))?;
-    Ok(())
+    set_content_protection(&app, enabled)
 }
 
 #[tauri::command]
 fn toggle_visibility(app: AppHandle) -> Result<bool, String> {
+    toggle_panel(&app)
+}
+
+/// Hides the panel, saving its geometry, or shows it where
+/// `summon_placement` puts it. Returns whether it's now visible.
+pub(crate) fn toggle_panel<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
     let window = app
         .get_webview_window("main")
         .ok_or("Failed to get main window")?;
@@ -142,36 +390,66 @@ fn toggle_visibility(app: AppHandle) -> Result<bool, String> {
         .map_err(|e| format!("Failed to check visibility: {}", e))?;
 
     if is_visible {
+        activation::cancel(&app.state::<AppCore>());
+        layout::save_window_state(app, &app.state::<AppCore>());
+        suspend::panel_hidden(&app.state::<AppCore>());
         window
             .hide()
             .map_err(|e| format!("Failed to hide window: {}", e))?;
     } else {
-        window
-            .show()
-            .map_err(|e| format!("Failed to show window: {}", e))?;
+        show_panel(app)?;
     }
 
     Ok(!is_visible)
 }
 
+/// Shows the panel where `summon_placement` puts it and emits `panel-shown`
+/// so the frontend focuses the input; does nothing when it's already
+/// visible. A page reloading after an unload misses the event and focuses
+/// the input itself once it has restored its snapshot.
+pub(crate) fn show_panel<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+
+    if window
+        .is_visible()
+        .map_err(|e| format!("Failed to check visibility: {}", e))?
+    {
+        return Ok(());
+    }
+
+    let core = app.state::<AppCore>();
+    if let Err(e) = suspend::panel_shown(app, &core) {
+        eprintln!("{}", e);
+    }
+    if let Err(e) = layout::place_for_summon(app, &core) {
+        eprintln!("{}", e);
+    }
+    window
+        .show()
+        .map_err(|e| format!("Failed to show window: {}", e))?;
+    activation::mark_visible(&core);
+    let _ = app.emit_to("main", "panel-shown", ());
+    Ok(())
+}
+
 #[tauri::command]
 fn set_shortcuts_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
-    let shortcuts = [
-        // Toggle visibility: Control+Option+Space (Mac) / Control+Alt+Space (Windows)
-        Shortcut::new(Some(Modifiers::ALT | Modifiers::CONTROL), Code::Space),
-        // Alternative: Control+Space
-        Shortcut::new(Some(Modifiers::CONTROL), Code::Space),
-    ];
-
-    if enabled {
-        app.global_shortcut()
-            .register_multiple(shortcuts)
-            .map_err(|e| format!("Failed to register shortcuts: {}", e))?;
-    } else {
-        for shortcut in shortcuts {
-            let _ = app.global_shortcut().unregister(shortcut);
-        }
-    }
+    shortcuts::set_global_shortcuts(&app, enabled)
+}
+
+// =============================================================================
+// SUBSYSTEM CONFIGURATION
+// =============================================================================
+
+fn set_content_protection<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    window
+        .set_content_protected(enabled)
+        .map_err(|e| format!("Failed to update content protection: {}", e))?;
     Ok(())
 }
 
@@ -181,7 +459,7 @@ fn set_shortcuts_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
 
 #[cfg(target_os = "macos")]
 #[allow(deprecated, unexpected_cfgs)]
-fn init_nspanel(app_handle: &AppHandle) {
+fn init_nspanel(app_handle: &AppHandle, core: &AppCore) {
     tauri_panel! {
         panel!(AIThingPanel {
             config: {
@@ -201,16 +479,19 @@ fn init_nspanel(app_handle: &AppHandle) {
     // Prevent panel from activating the app (required for fullscreen display)
     panel.set_style_mask(StyleMask::empty().nonactivating_panel().resizable().into());
 
-    // Allow panel to display over fullscreen windows and join all spaces
-    panel.set_collection_behavior(
-        CollectionBehavior::new()
-            .full_screen_auxiliary()
-            .can_join_all_spaces()
-            .into(),
-    );
+    // Allow panel to display over fullscreen windows and join all spaces,
+    // and keep it out of Stage Manager's stages
+    if let Err(e) = panel_behavior::apply(app_handle, core) {
+        eprintln!("{}", e);
+    }
 
     // Prevent panel from hiding when app deactivates
     panel.set_hides_on_deactivate(false);
+
+    // Give VoiceOver a title and role for the panel
+    if let Err(e) = voiceover::label_panel(app_handle, core) {
+        eprintln!("{}", e);
+    }
 }
 
 // =============================================================================
@@ -224,33 +505,9 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_process::init())
-        .plugin(
-            tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, shortcut, event| {
-                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
-                        let action = match shortcut.id() {
-                            // Toggle visibility: Control+Option+Space or Control+Space
-                            id if id
-                                == Shortcut::new(
-                                    Some(Modifiers::ALT | Modifiers::CONTROL),
-                                    Code::Space,
-                                )
-                                .id() =>
-                            {
-                                "toggle-visibility"
-                            }
-                            id if id
-                                == Shortcut::new(Some(Modifiers::CONTROL), Code::Space).id() =>
-                            {
-                                "toggle-visibility"
-                            }
-                            _ => return,
-                        };
-                        let _ = app.emit("shortcut-triggered", action);
-                    }
-                })
-                .build(),
-        );
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(shortcuts::plugin());
 
     #[cfg(target_os = "macos")]
     {
@@ -263,29 +520,43 @@ pub fn run() {
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
-            // Store app handle for emitting events
-            {
-                let mut handle = APP_HANDLE.write();
-                *handle = Some(app.handle().clone());
-            }
+            // Backend state, injected into commands as State<AppCore>
+            app.manage(AppCore::default());
+            let core = app.state::<AppCore>();
 
-            // Load stored settings from persistent storage
-            load_settings_from_store(app.handle());
+            // Load stored settings, with config.toml and policy applied on top
+            policy::load_policy(&core);
+            dotfile::load_dotfile(&core);
+            load_settings_from_store(app.handle(), &core);
+            theme::load_themes_from_store(app.handle(), &core);
+            memory::load_memories_from_store(app.handle(), &core);
+            profiles::load_profiles_from_store(app.handle(), &core);
+            let has_saved_geometry = layout::load_window_state(app.handle(), &core);
 
             // Platform-specific window initialization
             #[cfg(target_os = "macos")]
-            init_nspanel(app.app_handle());
-
-            // Register global shortcuts
-            let shortcuts = [
-                // Toggle visibility: Control+Option+Space (Mac) / Control+Alt+Space (Windows)
-                Shortcut::new(Some(Modifiers::ALT | Modifiers::CONTROL), Code::Space),
-                // Alternative: Control+Space
-                Shortcut::new(Some(Modifiers::CONTROL), Code::Space),
-            ];
-
-            if let Err(e) = app.global_shortcut().register_multiple(shortcuts) {
-                eprintln!("Failed to register global shortcuts: {}", e);
+            init_nspanel(app.app_handle(), &core);
+            if has_saved_geometry {
+                if let Err(e) = layout::restore_window_geometry(app.handle(), &core) {
+                    eprintln!("{}", e);
+                }
+            }
+            layout::track_window_geometry(app.handle());
+            theme::apply_active_theme(app.handle(), &core);
+            appearance::start_appearance_watcher(app.handle().clone());
+            quiet_hours::start_quiet_hours_watcher(app.handle().clone());
+            start_settings_watcher(app.handle().clone());
+            dotfile::start_dotfile_watcher(app.handle().clone());
+            panel_behavior::start_stage_manager_watcher(app.handle().clone());
+            hot_corners::start_hot_corner_watcher(app.handle().clone());
+            suspend::start_suspend_watcher(app.handle().clone());
+            event_tap::ensure_started(app.handle(), &core);
+
+            // Register global shortcuts, unless the user turned them off
+            if core.settings.read().shortcuts_enabled {
+                if let Err(e) = shortcuts::set_global_shortcuts(app.handle(), true) {
+                    eprintln!("{}", e);
+                }
             }
 
             Ok(())
@@ -297,8 +568,141 @@ pub fn run() {
             set_settings,
             set_screenshot_protection,
             toggle_visibility,
-            set_shortcuts_enabled
+            activation::get_activation_timings,
+            activation::mark_input_focused,
+            suspend::unload_webview,
+            suspend::take_webview_snapshot,
+            set_shortcuts_enabled,
+            calc::evaluate_expression,
+            dictionary::lookup_definition,
+            response_actions::open_url_in_default_browser,
+            response_actions::save_image_from_response,
+            response_actions::copy_code_block,
+            response_actions::copy_response,
+            markdown::render_markdown,
+            theme::get_theme,
+            theme::list_themes,
+            theme::set_theme,
+            appearance::set_background_material,
+            appearance::get_system_appearance,
+            appearance::get_accessibility_prefs,
+            layout::resize_to_content,
+            windows::detach_conversation,
+            windows::list_detached_conversations,
+            memory::add_memory,
+            memory::list_memories,
+            memory::delete_memory,
+            active_app::get_active_app,
+            quiet_hours::is_quiet_hours,
+            applescript::create_mail_draft,
+            applescript::get_selected_mail,
+            applescript::create_calendar_event,
+            applescript::get_upcoming_events,
+            applescript::create_reminder,
+            browser_context::get_browser_context,
+            contacts::lookup_contact,
+            window_capture::capture_frontmost_window,
+            drag_out::begin_drag,
+            quick_look::preview_file,
+            quick_look::close_preview,
+            policy::get_policy,
+            profiles::list_profiles,
+            profiles::get_active_profile,
+            profiles::save_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            providers::validate_api_key,
+            providers::list_models,
+            capabilities::get_model_capabilities,
+            mock_provider::mock_complete,
+            vcr::set_replay_mode,
+            portable_config::export_config,
+            portable_config::import_config,
+            secrets::get_credential_sources,
+            i18n::get_locale_strings,
+            i18n::set_locale,
+            voiceover::set_accessibility_announcements,
+            voiceover::announce_response_finished,
+            runtime::get_background_tasks,
+            lifecycle::quit_app,
+            lifecycle::relaunch_app,
+            lifecycle::factory_reset,
+            diagnostics::run_diagnostics,
+            hardware::get_hardware_info,
+            sleep_prevention::set_sleep_prevention,
+            sleep_prevention::get_sleep_prevention
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Let background tasks finish what they're doing before exit
+            if let tauri::RunEvent::Exit = event {
+                runtime::shutdown(&app.state::<AppCore>(), TASK_SHUTDOWN_TIMEOUT);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Harness;
+
+    #[test]
+    fn settings_survive_a_restart() {
+        let harness = Harness::new();
+
+        let app = harness.launch();
+        let core = app.state::<AppCore>();
+        let settings = AppSettings {
+            open_at_login: true,
+            excluded_apps: vec!["com.example.bank".to_string()],
+            ..AppSettings::default()
+        };
+        replace_settings(app.handle(), &core, settings);
+
+        let app = harness.launch();
+        let core = app.state::<AppCore>();
+        load_settings_from_store(app.handle(), &core);
+
+        let settings = core.settings.read();
+        assert!(settings.open_at_login);
+        assert_eq!(settings.excluded_apps, vec!["com.example.bank"]);
+    }
+
+    #[test]
+    fn missing_store_loads_defaults() {
+        let app = Harness::new().launch();
+        let core = app.state::<AppCore>();
+        load_settings_from_store(app.handle(), &core);
+
+        let settings = core.settings.read();
+        assert!(settings.shortcuts_enabled);
+        assert_eq!(settings.excluded_apps, AppSettings::default().excluded_apps);
+    }
+
+    #[test]
+    fn policy_overrides_replaced_settings() {
+        let app = Harness::new().launch();
+        let core = app.state::<AppCore>();
+        let mut pinned = serde_json::Map::new();
+        pinned.insert("show_in_screenshot".to_string(), false.into());
+        policy::set_policy(
+            &core,
+            policy::Policy {
+                settings: pinned,
+                ..Default::default()
+            },
+        );
+
+        let settings = AppSettings {
+            show_in_screenshot: true,
+            open_at_login: true,
+            ..AppSettings::default()
+        };
+        replace_settings(app.handle(), &core, settings);
+
+        let settings = core.settings.read();
+        assert!(!settings.show_in_screenshot);
+        assert!(settings.open_at_login);
+    }
 }
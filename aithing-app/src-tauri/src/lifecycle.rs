@@ -0,0 +1,252 @@
+//! App lifecycle
+//!
+//! Quitting without losing the last answer. Generations register while
+//! they stream; `quit_app` stops new ones from starting, lets the running
+//! ones finish (or cancels them when forced), then saves window state and
+//! settings and releases the global shortcuts before exiting. Background
+//! tasks are stopped by the runtime on the way out. `relaunch_app` does the
+//! same and starts the app again.
+//!
+//! `factory_reset` wipes everything the app has stored (settings, profiles,
+//! themes, memories, window geometry, chat history and saved keys in the
+//! webview's storage, and caches) and relaunches. It asks for confirmation
+//! in a native dialog first, so the webview can't skip that step.
+//! Admin policy and `config.toml` are left alone; the user or their admin
+//! owns those files.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::i18n::t;
+use crate::{app_nap, layout, shortcuts, sleep_prevention, STORE_FILE};
+
+/// How long a normal quit waits for generations to finish.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long cancelled generations get to wind down.
+const CANCEL_GRACE: Duration = Duration::from_secs(2);
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct LifecycleState {
+    in_flight: AtomicUsize,
+    /// Set once quitting starts; no new generations after that
+    quitting: AtomicBool,
+    /// Checked by streaming generations between chunks
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Registration of one streaming generation, released on drop. Keeps App
+/// Nap from stalling the stream while the panel is hidden.
+pub struct Generation<'a> {
+    lifecycle: &'a LifecycleState,
+    /// True once the generation should stop early
+    pub cancelled: Arc<AtomicBool>,
+    _activity: app_nap::Activity,
+}
+
+impl Drop for Generation<'_> {
+    fn drop(&mut self) {
+        self.lifecycle.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// =============================================================================
+// GENERATIONS
+// =============================================================================
+
+/// Registers a generation for the duration of the returned guard. Fails
+/// once the app is quitting.
+pub fn begin_generation(core: &AppCore) -> Result<Generation<'_>, String> {
+    let lifecycle = &core.lifecycle;
+    if lifecycle.quitting.load(Ordering::SeqCst) {
+        return Err("AIThing is quitting".to_string());
+    }
+    lifecycle.in_flight.fetch_add(1, Ordering::SeqCst);
+    Ok(Generation {
+        lifecycle,
+        cancelled: lifecycle.cancelled.clone(),
+        _activity: app_nap::begin("Streaming a response"),
+    })
+}
+
+/// Waits up to `timeout` for every generation to finish; returns whether
+/// they did.
+fn wait_for_generations(core: &AppCore, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while core.lifecycle.in_flight.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(DRAIN_POLL_INTERVAL);
+    }
+    true
+}
+
+/// Stops new generations, then waits for running ones: up to
+/// `DRAIN_TIMEOUT` before cancelling them, or not at all when `force`d.
+fn drain_generations(core: &AppCore, force: bool) {
+    core.lifecycle.quitting.store(true, Ordering::SeqCst);
+    if !force && wait_for_generations(core, DRAIN_TIMEOUT) {
+        return;
+    }
+    core.lifecycle.cancelled.store(true, Ordering::SeqCst);
+    if !wait_for_generations(core, CANCEL_GRACE) {
+        eprintln!("Quitting with generations still running");
+    }
+}
+
+// =============================================================================
+// EXIT AND RESET
+// =============================================================================
+
+/// Drains generations, then saves window state and settings and releases
+/// shortcuts, ready for the process to exit.
+async fn prepare_exit(app: &AppHandle, force: bool) -> Result<(), String> {
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        drain_generations(&handle.state::<AppCore>(), force)
+    })
+    .await
+    .map_err(|e| format!("Failed to stop generations: {}", e))?;
+
+    let core = app.state::<AppCore>();
+    layout::save_window_state(app, &core);
+    crate::save_settings_to_store(app, &core);
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Err(e) = store.save() {
+            eprintln!("Failed to save store: {}", e);
+        }
+    }
+    if let Err(e) = shortcuts::set_global_shortcuts(app, false) {
+        eprintln!("{}", e);
+    }
+    sleep_prevention::release_all(&core);
+    Ok(())
+}
+
+/// Deletes everything the app has stored. Errors are collected rather than
+/// stopping the wipe partway.
+fn wipe_app_data(app: &AppHandle) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    match app.store(STORE_FILE) {
+        Ok(store) => {
+            store.clear();
+            if let Err(e) = store.save() {
+                errors.push(format!("Failed to clear store: {}", e));
+            }
+        }
+        Err(e) => errors.push(format!("Failed to open store: {}", e)),
+    }
+
+    // Chat history and keys saved by the frontend live in the webview's
+    // local storage
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.clear_all_browsing_data() {
+            errors.push(format!("Failed to clear {} storage: {}", window.label(), e));
+        }
+    }
+
+    for dir in [app.path().app_data_dir(), app.path().app_cache_dir()]
+        .into_iter()
+        .flatten()
+    {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                errors.push(format!("Failed to remove {}: {}", dir.display(), e));
+            }
+        }
+    }
+    errors
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Quits once in-flight generations are done, cancelling them right away
+/// when `force` is set, and after saving window state and settings and
+/// unregistering shortcuts.
+#[tauri::command]
+pub async fn quit_app(app: AppHandle, force: bool) -> Result<(), String> {
+    prepare_exit(&app, force).await?;
+    app.exit(0);
+    Ok(())
+}
+
+/// Quits as `quit_app` does, waiting for generations, and starts the app
+/// again.
+#[tauri::command]
+pub async fn relaunch_app(app: AppHandle) -> Result<(), String> {
+    prepare_exit(&app, false).await?;
+    app.restart();
+}
+
+fn confirm_reset(app: &AppHandle, core: &AppCore) -> bool {
+    app.dialog()
+        .message(t(core, "factory-reset-message", &[]))
+        .title(t(core, "factory-reset-title", &[]))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            t(core, "factory-reset-confirm", &[]),
+            t(core, "factory-reset-cancel", &[]),
+        ))
+        .blocking_show()
+}
+
+/// Once the user confirms, cancels generations, wipes all stored data, and
+/// relaunches with defaults. Nothing is saved on the way out, so the wipe
+/// sticks. Returns without doing anything if the user cancels.
+#[tauri::command]
+pub async fn factory_reset(app: AppHandle) -> Result<(), String> {
+    if !confirm_reset(&app, &app.state::<AppCore>()) {
+        return Ok(());
+    }
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        drain_generations(&handle.state::<AppCore>(), true)
+    })
+    .await
+    .map_err(|e| format!("Failed to stop generations: {}", e))?;
+    if let Err(e) = shortcuts::set_global_shortcuts(&app, false) {
+        eprintln!("{}", e);
+    }
+
+    for error in wipe_app_data(&app) {
+        eprintln!("{}", error);
+    }
+    app.restart();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quitting_cancels_generations_and_refuses_new_ones() {
+        let core = AppCore::default();
+        let generation = begin_generation(&core).unwrap();
+        let cancelled = generation.cancelled.clone();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| drain_generations(&core, true));
+            while !cancelled.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            drop(generation);
+        });
+
+        assert_eq!(core.lifecycle.in_flight.load(Ordering::SeqCst), 0);
+        assert!(begin_generation(&core).is_err());
+    }
+}
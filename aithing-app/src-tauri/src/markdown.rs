@@ -0,0 +1,200 @@
+//! Markdown rendering with syntax highlighting
+//!
+//! Responses are rendered to HTML here rather than in the webview so long code
+//! answers don't stall the UI thread while streaming. Code blocks are
+//! highlighted with syntect using inline styles, so no stylesheet is needed.
+
+use once_cell::sync::Lazy;
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::response_actions;
+
+const DEFAULT_DARK_THEME: &str = "base16-ocean.dark";
+const DEFAULT_LIGHT_THEME: &str = "InspiredGitHub";
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn parser_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS
+}
+
+/// Resolves "dark"/"light" or an exact syntect theme name, falling back to the
+/// dark theme the panel ships with.
+pub(crate) fn resolve_theme(name: Option<&str>) -> &'static Theme {
+    let name = match name {
+        Some("light") => DEFAULT_LIGHT_THEME,
+        Some("dark") | None => DEFAULT_DARK_THEME,
+        Some(name) => name,
+    };
+    THEME_SET
+        .themes
+        .get(name)
+        .unwrap_or(&THEME_SET.themes[DEFAULT_DARK_THEME])
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn highlight_code(code: &str, language: &str, theme: &Theme) -> String {
+    let syntax = SYNTAX_SET
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+
+    highlighted_html_for_string(code, &SYNTAX_SET, syntax, theme)
+        .unwrap_or_else(|_| format!("<pre><code>{}</code></pre>", escape_html(code)))
+}
+
+/// Renders markdown to HTML, replacing fenced and indented code blocks with
+/// highlighted HTML. Raw HTML in the source is escaped rather than passed
+/// through, since responses are inserted into the webview as-is. Links and
+/// images whose destination isn't http, https, or mailto are reduced to
+/// their text.
+pub fn render(text: &str, theme: &Theme) -> String {
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    // Whether each open link or image was dropped, so its end is dropped too
+    let mut dropped_links: Vec<bool> = Vec::new();
+
+    for event in Parser::new_ext(text, parser_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, code)) = code_block.take() {
+                    let highlighted = highlight_code(&code, &language, theme);
+                    events.push(Event::Html(CowStr::from(highlighted)));
+                }
+            }
+            Event::Text(chunk) if code_block.is_some() => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&chunk);
+                }
+            }
+            Event::Start(Tag::Link { ref dest_url, .. } | Tag::Image { ref dest_url, .. }) => {
+                let allowed = response_actions::is_allowed_url(dest_url);
+                dropped_links.push(!allowed);
+                if allowed {
+                    events.push(event);
+                }
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                if !dropped_links.pop().unwrap_or(false) {
+                    events.push(event);
+                }
+            }
+            Event::Html(raw) | Event::InlineHtml(raw) => events.push(Event::Text(raw)),
+            event => events.push(event),
+        }
+    }
+
+    let mut output = String::with_capacity(text.len() * 2);
+    html::push_html(&mut output, events.into_iter());
+    output
+}
+
+/// Strips markdown syntax, keeping the text, code, list markers, and block
+/// breaks, for pasting into places that don't understand markdown.
+pub fn to_plain_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for event in Parser::new_ext(text, parser_options()) {
+        match event {
+            Event::Text(chunk) | Event::Code(chunk) => output.push_str(&chunk),
+            Event::SoftBreak | Event::HardBreak => output.push('\n'),
+            Event::Start(Tag::Item) => {
+                // Nested items start inside their parent's line
+                if !output.is_empty() && !output.ends_with('\n') {
+                    output.push('\n');
+                }
+                output.push_str("- ");
+            }
+            Event::End(TagEnd::Item | TagEnd::TableRow | TagEnd::TableHead) => output.push('\n'),
+            Event::End(TagEnd::TableCell) => output.push('\t'),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::BlockQuote(_)
+                | TagEnd::List(_)
+                | TagEnd::Table,
+            ) => {
+                let trimmed = output.trim_end_matches('\n').len();
+                output.truncate(trimmed);
+                output.push_str("\n\n");
+            }
+            _ => {}
+        }
+    }
+
+    output.trim_end().to_string()
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Wraps rendered HTML in a `markdown-body` element carrying the minimum font
+/// size, which the stylesheet applies to every text size inside it.
+fn with_min_font_size(html: &str, min_font_size: Option<f64>) -> String {
+    let min_font_size = min_font_size.unwrap_or(0.0).max(0.0);
+    format!(
+        "<div class=\"markdown-body\" style=\"--min-font-size: {}px\">{}</div>",
+        min_font_size, html
+    )
+}
+
+/// Renders a response for the panel, honoring the minimum font size setting.
+#[tauri::command]
+pub async fn render_markdown(
+    core: State<'_, AppCore>,
+    text: String,
+    theme: Option<String>,
+) -> Result<String, String> {
+    let min_font_size = core.settings.read().markdown_min_font_size;
+    let html = render(&text, resolve_theme(theme.as_deref()));
+    Ok(with_min_font_size(&html, min_font_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_and_mail_links_stay_live() {
+        let theme = resolve_theme(None);
+        let html = render(
+            "[docs](https://example.com) [mail](mailto:a@example.com) \
+             [x](javascript:alert(1)) [y](JavaScript:alert(1)) [z](file:///etc/passwd) \
+             ![img](data:image/svg+xml,x) [rel](/settings)",
+            theme,
+        );
+
+        assert!(html.contains("<a href=\"https://example.com\">docs</a>"));
+        assert!(html.contains("<a href=\"mailto:a@example.com\">mail</a>"));
+        assert_eq!(html.matches("<a ").count(), 2);
+        assert!(!html.contains("<img"));
+        assert!(!html.to_lowercase().contains("javascript:"));
+        assert!(html.contains("rel"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Persistent user memories
+//!
+//! Durable facts and preferences ("I write Rust", "My timezone is CET") saved
+//! by the user, or by the model once the user approves, and kept locally in
+//! the app store.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::STORE_FILE;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemorySource {
+    User,
+    Model,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Memory {
+    pub id: String,
+    pub content: String,
+    pub source: MemorySource,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct MemoryState {
+    memories: RwLock<Vec<Memory>>,
+}
+
+// =============================================================================
+// MEMORY STORAGE
+// =============================================================================
+
+fn save_memories_to_store<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Ok(json) = serde_json::to_value(&*core.memory.memories.read()) {
+            store.set("memories", json);
+            let _ = store.save();
+        }
+    }
+}
+
+pub fn load_memories_from_store<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Some(memories_json) = store.get("memories") {
+            if let Ok(memories) = serde_json::from_value::<Vec<Memory>>(memories_json) {
+                *core.memory.memories.write() = memories;
+            }
+        }
+    }
+}
+
+/// Saves a memory. Memories proposed by the model must already have been
+/// approved by the user before this is called.
+pub fn add<R: Runtime>(
+    app: &AppHandle<R>,
+    content: &str,
+    source: MemorySource,
+) -> Result<Memory, String> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err("Memory cannot be empty".to_string());
+    }
+
+    let memory = Memory {
+        id: uuid::Uuid::new_v4().to_string(),
+        content: content.to_string(),
+        source,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0),
+    };
+
+    let core = app.state::<AppCore>();
+    core.memory.memories.write().push(memory.clone());
+    save_memories_to_store(app, &core);
+    Ok(memory)
+}
+
+pub fn delete<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let core = app.state::<AppCore>();
+    {
+        let mut memories = core.memory.memories.write();
+        let count = memories.len();
+        memories.retain(|memory| memory.id != id);
+        if memories.len() == count {
+            return Err(format!("Memory not found: {}", id));
+        }
+    }
+    save_memories_to_store(app, &core);
+    Ok(())
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn add_memory(
+    app: AppHandle,
+    content: String,
+    source: Option<MemorySource>,
+) -> Result<Memory, String> {
+    add(&app, &content, source.unwrap_or(MemorySource::User))
+}
+
+#[tauri::command]
+pub fn list_memories(core: State<'_, AppCore>) -> Vec<Memory> {
+    core.memory.memories.read().clone()
+}
+
+#[tauri::command]
+pub fn delete_memory(app: AppHandle, id: String) -> Result<(), String> {
+    delete(&app, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::Harness;
+
+    #[test]
+    fn memories_persist_until_deleted() {
+        let harness = Harness::new();
+
+        let app = harness.launch();
+        let memory = add(app.handle(), "  I write Rust  ", MemorySource::User).unwrap();
+        assert_eq!(memory.content, "I write Rust");
+        assert!(add(app.handle(), "   ", MemorySource::Model).is_err());
+
+        let app = harness.launch();
+        let core = app.state::<AppCore>();
+        load_memories_from_store(app.handle(), &core);
+        let ids: Vec<String> = list_memories(core.clone())
+            .into_iter()
+            .map(|memory| memory.id)
+            .collect();
+        assert_eq!(ids, vec![memory.id.clone()]);
+
+        delete(app.handle(), &memory.id).unwrap();
+        assert!(list_memories(core.clone()).is_empty());
+        assert_eq!(
+            delete(app.handle(), &memory.id),
+            Err(format!("Memory not found: {}", memory.id))
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Mock AI provider
+//!
+//! A `mock` provider that streams canned or scripted responses over a channel
+//! the way a real provider streams tokens, so the chat UI and stream handling
+//! can be worked on without API keys or credits. Latency, jitter, and
+//! injected failures come from `AppSettings::mock_provider`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+use tauri::ipc::Channel;
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::{lifecycle, policy};
+
+const CANNED_RESPONSE: &str = "This response comes from the mock provider. \
+No request was sent to a model, and no API credits were used. Add responses \
+to the mock provider's script in settings to replay your own.";
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockProviderSettings {
+    /// Delay before the first chunk, like a model's time to first token
+    pub first_chunk_ms: u64,
+    /// Delay between chunks
+    pub chunk_interval_ms: u64,
+    /// Random extra delay of up to this much before each chunk
+    pub jitter_ms: u64,
+    /// Chance (0.0 to 1.0) that a response fails partway through
+    pub failure_rate: f64,
+    /// Responses replayed in order, wrapping around. When empty, each prompt
+    /// gets a canned reply that echoes it.
+    pub script: Vec<String>,
+}
+
+impl Default for MockProviderSettings {
+    fn default() -> Self {
+        Self {
+            first_chunk_ms: 400,
+            chunk_interval_ms: 30,
+            jitter_ms: 40,
+            failure_rate: 0.0,
+            script: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "camelCase")]
+pub enum StreamEvent {
+    Delta { text: String },
+    Done,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct MockState {
+    /// Index of the next scripted response
+    next_response: AtomicUsize,
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Uniform in [0, 1). Latency jitter and failure injection don't need a real
+/// RNG, and the std hasher's random keys are enough.
+fn random_unit() -> f64 {
+    (RandomState::new().hash_one(()) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn next_response(core: &AppCore, settings: &MockProviderSettings, prompt: &str) -> String {
+    if settings.script.is_empty() {
+        return format!("You said: \"{}\"\n\n{}", prompt, CANNED_RESPONSE);
+    }
+    let index = core.mock.next_response.fetch_add(1, Ordering::Relaxed) % settings.script.len();
+    settings.script[index].clone()
+}
+
+fn stream_response(
+    settings: &MockProviderSettings,
+    response: &str,
+    on_event: &Channel<StreamEvent>,
+    cancelled: &AtomicBool,
+) -> Result<(), String> {
+    let chunks: Vec<&str> = response.split_inclusive(' ').collect();
+    // Picked up front so a failing response still streams some text first
+    let fail_at = (random_unit() < settings.failure_rate)
+        .then(|| (random_unit() * chunks.len() as f64) as usize);
+
+    std::thread::sleep(Duration::from_millis(settings.first_chunk_ms));
+    for (index, chunk) in chunks.iter().enumerate() {
+        if fail_at == Some(index) {
+            return Err("Mock provider failure (injected)".to_string());
+        }
+        if cancelled.load(Ordering::SeqCst) {
+            return Err("Response cancelled".to_string());
+        }
+        if index > 0 {
+            let jitter = (random_unit() * settings.jitter_ms as f64) as u64;
+            std::thread::sleep(Duration::from_millis(settings.chunk_interval_ms + jitter));
+        }
+        on_event
+            .send(StreamEvent::Delta {
+                text: chunk.to_string(),
+            })
+            .map_err(|e| format!("Failed to send stream event: {}", e))?;
+    }
+
+    on_event
+        .send(StreamEvent::Done)
+        .map_err(|e| format!("Failed to send stream event: {}", e))
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Streams a mock reply to `prompt` as `delta` events followed by `done`. An
+/// injected failure, or the app quitting, ends the stream early with an
+/// error instead of `done`.
+#[tauri::command]
+pub async fn mock_complete(
+    core: State<'_, AppCore>,
+    prompt: String,
+    on_event: Channel<StreamEvent>,
+) -> Result<(), String> {
+    policy::check_provider(&core, "mock")?;
+    let generation = lifecycle::begin_generation(&core)?;
+    let cancelled = generation.cancelled.clone();
+    let settings = core.settings.read().mock_provider.clone();
+    let response = next_response(&core, &settings, &prompt);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        stream_response(&settings, &response, &on_event, &cancelled)
+    })
+    .await
+    .map_err(|e| format!("Mock stream stopped: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_replays_in_order_and_wraps() {
+        let core = AppCore::default();
+        let settings = MockProviderSettings {
+            script: vec!["first".to_string(), "second".to_string()],
+            ..MockProviderSettings::default()
+        };
+
+        let replies: Vec<String> = (0..3)
+            .map(|_| next_response(&core, &settings, "hi"))
+            .collect();
+        assert_eq!(replies, vec!["first", "second", "first"]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Panel window-manager behavior
+//!
+//! Decides how the panel takes part in Spaces, Stage Manager, and window
+//! cycling, expressed as an `NSWindowCollectionBehavior`. With Stage Manager
+//! on, a normal window is pulled into whichever stage was active when it
+//! appeared and disappears when the user switches stages; the panel opts out
+//! as either stationary (stays put on every stage) or transient (floats above
+//! stages without belonging to one). Users can also take the panel out of
+//! Mission Control and Cmd-` window cycling altogether.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::app_core::AppCore;
+use crate::runtime;
+use crate::AppSettings;
+
+/// Stage Manager is toggled from Control Center; a short poll keeps the
+/// panel's behavior in step without observing WindowManager notifications.
+const STAGE_MANAGER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// NSWindowCollectionBehavior bits
+const CAN_JOIN_ALL_SPACES: u64 = 1 << 0;
+const TRANSIENT: u64 = 1 << 3;
+const STATIONARY: u64 = 1 << 4;
+const IGNORES_CYCLE: u64 = 1 << 6;
+const FULL_SCREEN_AUXILIARY: u64 = 1 << 8;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageManagerBehavior {
+    /// Shown on every stage, unaffected by stage switches
+    #[default]
+    Stationary,
+    /// Floats above stages and is left out of them entirely
+    Transient,
+}
+
+// =============================================================================
+// COLLECTION BEHAVIOR
+// =============================================================================
+
+/// True when Stage Manager is turned on.
+#[cfg(target_os = "macos")]
+pub fn stage_manager_enabled() -> bool {
+    crate::system_prefs::bool_value(Some("com.apple.WindowManager"), "GloballyEnabled")
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn stage_manager_enabled() -> bool {
+    false
+}
+
+/// The panel's collection behavior for `settings`. It always joins every
+/// Space and shows over fullscreen apps; Stage Manager adds the configured
+/// opt-out, unless the panel is excluded from Mission Control, which makes
+/// it transient regardless.
+pub fn collection_behavior(settings: &AppSettings, stage_manager: bool) -> u64 {
+    let mut behavior = CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY;
+    if settings.exclude_from_mission_control {
+        behavior |= TRANSIENT | IGNORES_CYCLE;
+    } else if stage_manager {
+        behavior |= match settings.stage_manager_behavior {
+            StageManagerBehavior::Stationary => STATIONARY,
+            StageManagerBehavior::Transient => TRANSIENT,
+        };
+    }
+    behavior
+}
+
+/// Applies the collection behavior for the current settings and Stage
+/// Manager state to the main panel.
+#[cfg(target_os = "macos")]
+pub fn apply<R: Runtime>(app: &AppHandle<R>, core: &AppCore) -> Result<(), String> {
+    let behavior = collection_behavior(&core.settings.read(), stage_manager_enabled());
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+
+    app.run_on_main_thread(move || {
+        use objc2::msg_send;
+        use objc2::runtime::AnyObject;
+
+        let ns_window = match window.ns_window() {
+            Ok(ns_window) => ns_window as *mut AnyObject,
+            Err(e) => {
+                eprintln!("Failed to get native window: {}", e);
+                return;
+            }
+        };
+        unsafe {
+            let _: () = msg_send![ns_window, setCollectionBehavior: behavior as usize];
+        }
+    })
+    .map_err(|e| format!("Failed to update collection behavior: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn apply<R: Runtime>(_app: &AppHandle<R>, _core: &AppCore) -> Result<(), String> {
+    Ok(())
+}
+
+/// Re-applies the collection behavior whenever Stage Manager is turned on or
+/// off.
+pub fn start_stage_manager_watcher(app: AppHandle) {
+    runtime::spawn(app, "stage-manager", |app, shutdown| {
+        let mut enabled = stage_manager_enabled();
+
+        while shutdown.sleep(STAGE_MANAGER_POLL_INTERVAL) {
+            let now_enabled = stage_manager_enabled();
+            if now_enabled == enabled {
+                continue;
+            }
+            enabled = now_enabled;
+
+            if let Err(e) = apply(app, &app.state::<AppCore>()) {
+                eprintln!("{}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_manager_adds_the_configured_opt_out() {
+        let mut settings = AppSettings::default();
+        let base = CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY;
+
+        assert_eq!(collection_behavior(&settings, false), base);
+        assert_eq!(collection_behavior(&settings, true), base | STATIONARY);
+
+        settings.stage_manager_behavior = StageManagerBehavior::Transient;
+        assert_eq!(collection_behavior(&settings, true), base | TRANSIENT);
+    }
+
+    #[test]
+    fn mission_control_exclusion_overrides_stage_manager() {
+        let settings = AppSettings {
+            exclude_from_mission_control: true,
+            ..AppSettings::default()
+        };
+        let excluded = CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY | TRANSIENT | IGNORES_CYCLE;
+
+        assert_eq!(collection_behavior(&settings, false), excluded);
+        assert_eq!(collection_behavior(&settings, true), excluded);
+    }
+}
@@ -0,0 +1,207 @@
+//! Organization policy
+//!
+//! Administrators can deploy a policy file that pins setting values and
+//! restricts providers and history. Pinned settings are merged over the
+//! user's settings on load and on every save, so they can't be changed from
+//! the app. Provider commands refuse providers the policy doesn't allow.
+//! History lives only in the webview, so `disable_history` is enforced by
+//! the frontend. The policy is only honored when the file is owned by root
+//! and not writable by anyone else, which keeps users from editing it
+//! themselves; the file isn't signed.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::AppSettings;
+
+const POLICY_PATH: &str = "/Library/Application Support/AIThing/policy.json";
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// Setting values (keyed like `AppSettings`) that override the user's
+    pub settings: Map<String, Value>,
+    /// Providers users may choose from; empty allows every provider
+    pub allowed_providers: Vec<String>,
+    /// Don't keep conversation history
+    pub disable_history: bool,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct PolicyState {
+    policy: RwLock<Option<Policy>>,
+}
+
+// =============================================================================
+// POLICY LOADING
+// =============================================================================
+
+/// Only a root-owned file that no one else can write counts as a policy.
+#[cfg(unix)]
+fn is_admin_managed(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    std::fs::metadata(path)
+        .map(|metadata| metadata.uid() == 0 && metadata.mode() & 0o022 == 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_admin_managed(_path: &Path) -> bool {
+    false
+}
+
+pub fn load_policy(core: &AppCore) {
+    let path = Path::new(POLICY_PATH);
+    if !path.exists() {
+        return;
+    }
+    if !is_admin_managed(path) {
+        eprintln!("Ignoring policy at {}: not admin-managed", POLICY_PATH);
+        return;
+    }
+
+    let policy = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str::<Policy>(&json).map_err(|e| e.to_string()));
+    match policy {
+        Ok(policy) => set_policy(core, policy),
+        Err(e) => eprintln!("Failed to load policy: {}", e),
+    }
+}
+
+pub(crate) fn set_policy(core: &AppCore, policy: Policy) {
+    *core.policy.policy.write() = Some(policy);
+}
+
+// =============================================================================
+// ENFORCEMENT
+// =============================================================================
+
+/// Overwrites `settings` with every value the policy pins. Each pin is
+/// applied on its own, so one that doesn't fit the settings shape is skipped
+/// without unlocking the rest.
+pub fn enforce(core: &AppCore, settings: &mut AppSettings) {
+    let policy = core.policy.policy.read();
+    let Some(policy) = policy.as_ref() else {
+        return;
+    };
+    if policy.settings.is_empty() {
+        return;
+    }
+
+    for (key, value) in &policy.settings {
+        let Ok(Value::Object(mut merged)) = serde_json::to_value(&*settings) else {
+            return;
+        };
+        merged.insert(key.clone(), value.clone());
+        match serde_json::from_value::<AppSettings>(Value::Object(merged)) {
+            Ok(enforced) => *settings = enforced,
+            Err(e) => eprintln!("Failed to apply policy setting {}: {}", key, e),
+        }
+    }
+}
+
+/// Fails unless the policy allows `provider`, named as the frontend names
+/// it (`anthropic`, `openai`, `google`, `mock`).
+pub fn check_provider(core: &AppCore, provider: &str) -> Result<(), String> {
+    let policy = core.policy.policy.read();
+    let Some(policy) = policy.as_ref() else {
+        return Ok(());
+    };
+    if policy.allowed_providers.is_empty()
+        || policy
+            .allowed_providers
+            .iter()
+            .any(|allowed| allowed == provider)
+    {
+        return Ok(());
+    }
+    Err(format!(
+        "The {} provider is disabled by your organization",
+        provider
+    ))
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns the active policy, if any, so the frontend can lock the controls
+/// and restrictions it covers.
+#[tauri::command]
+pub fn get_policy(core: State<'_, AppCore>) -> Option<Policy> {
+    core.policy.policy.read().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_values_that_dont_fit_are_ignored() {
+        let core = AppCore::default();
+        let mut pinned = Map::new();
+        pinned.insert("shortcuts_enabled".to_string(), Value::from("sometimes"));
+        pinned.insert("include_active_window".to_string(), Value::from(true));
+        set_policy(
+            &core,
+            Policy {
+                settings: pinned,
+                ..Default::default()
+            },
+        );
+
+        let mut settings = AppSettings {
+            open_at_login: true,
+            ..AppSettings::default()
+        };
+        enforce(&core, &mut settings);
+
+        assert!(settings.open_at_login);
+        assert!(settings.shortcuts_enabled);
+        // The bad pin doesn't unlock the good one
+        assert!(settings.include_active_window);
+    }
+
+    #[test]
+    fn only_allowed_providers_pass() {
+        let core = AppCore::default();
+        assert!(check_provider(&core, "openai").is_ok());
+
+        set_policy(
+            &core,
+            Policy {
+                allowed_providers: vec!["anthropic".to_string()],
+                ..Default::default()
+            },
+        );
+        assert!(check_provider(&core, "anthropic").is_ok());
+        assert!(check_provider(&core, "openai").is_err());
+        assert!(check_provider(&core, "mock").is_err());
+    }
+
+    #[test]
+    fn no_policy_leaves_settings_alone() {
+        let core = AppCore::default();
+        let mut settings = AppSettings {
+            include_active_window: true,
+            ..AppSettings::default()
+        };
+        enforce(&core, &mut settings);
+
+        assert!(settings.include_active_window);
+    }
+}
@@ -0,0 +1,242 @@
+//! Portable configuration
+//!
+//! Exports settings, themes, memories, and profiles (with their personas) to
+//! one JSON file that can be imported on another Mac or handed to a team as a
+//! baseline. There are no saved prompts to carry yet. API keys are
+//! only included when a passphrase is given, and are then encrypted with a
+//! key derived from it; everything else is plain JSON.
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::{memory, profiles, theme, AppSettings, STORE_FILE};
+
+const FORMAT: &str = "aithing-config";
+const FORMAT_VERSION: u32 = 1;
+/// Store keys carried in the file. Settings are listed separately because
+/// they're imported through `replace_settings` so policy still applies.
+const STORE_KEYS: &[&str] = &[
+    "themes",
+    "active_theme",
+    "memories",
+    "profiles",
+    "active_profile",
+];
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigFile {
+    format: String,
+    version: u32,
+    #[serde(default)]
+    settings: Option<AppSettings>,
+    /// Other store values, keyed like the store. Sections left out of a file
+    /// are left alone on import.
+    #[serde(default)]
+    store: Map<String, Value>,
+    /// Values the frontend owns, such as the selected model
+    #[serde(default)]
+    frontend: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_keys: Option<EncryptedSecrets>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedSecrets {
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedConfig {
+    /// Frontend-owned values for the frontend to restore
+    pub frontend: Map<String, Value>,
+    /// Provider -> API key, when the file has keys and the passphrase opened
+    /// them
+    pub api_keys: Option<BTreeMap<String, String>>,
+    /// The file has API keys but no passphrase was given
+    pub api_keys_locked: bool,
+}
+
+// =============================================================================
+// ENCRYPTION
+// =============================================================================
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn encrypt_secrets(
+    secrets: &BTreeMap<String, String>,
+    passphrase: &str,
+) -> Result<EncryptedSecrets, String> {
+    let plaintext =
+        serde_json::to_vec(secrets).map_err(|e| format!("Failed to serialize API keys: {}", e))?;
+
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&key))
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|e| format!("Failed to encrypt API keys: {}", e))?;
+
+    Ok(EncryptedSecrets {
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt_secrets(
+    secrets: &EncryptedSecrets,
+    passphrase: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let decode = |value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|e| format!("Failed to decode API keys: {}", e))
+    };
+    let salt = decode(&secrets.salt)?;
+    let nonce = decode(&secrets.nonce)?;
+    let ciphertext = decode(&secrets.ciphertext)?;
+    if nonce.len() != 24 {
+        return Err("Failed to decode API keys: bad nonce".to_string());
+    }
+
+    let key = derive_key(passphrase, &salt)?;
+    let plaintext = XChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "Wrong passphrase, or the API keys are damaged".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse API keys: {}", e))
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Writes the current configuration to `path`. `api_keys` are included only
+/// with a `passphrase`, and are encrypted with it.
+#[tauri::command]
+pub fn export_config(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    path: String,
+    frontend: Option<Map<String, Value>>,
+    api_keys: Option<BTreeMap<String, String>>,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    let store = app
+        .store(STORE_FILE)
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    let api_keys = match (api_keys, passphrase.as_deref().map(str::trim)) {
+        (Some(keys), Some(passphrase)) if !passphrase.is_empty() => {
+            Some(encrypt_secrets(&keys, passphrase)?)
+        }
+        (Some(_), _) => return Err("A passphrase is required to export API keys".to_string()),
+        (None, _) => None,
+    };
+
+    let file = ConfigFile {
+        format: FORMAT.to_string(),
+        version: FORMAT_VERSION,
+        settings: Some(core.settings.read().clone()),
+        store: STORE_KEYS
+            .iter()
+            .filter_map(|key| Some((key.to_string(), store.get(*key)?)))
+            .collect(),
+        frontend: frontend.unwrap_or_default(),
+        api_keys,
+    };
+
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize configuration: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Applies the configuration in `path`. Sections present in the file replace
+/// the current ones; policy still applies on top of imported settings.
+#[tauri::command]
+pub fn import_config(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<ImportedConfig, String> {
+    let json =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let file = serde_json::from_str::<ConfigFile>(&json)
+        .map_err(|e| format!("Failed to parse configuration: {}", e))?;
+    if file.format != FORMAT {
+        return Err("Not an AIThing configuration file".to_string());
+    }
+    if file.version > FORMAT_VERSION {
+        return Err("This configuration was exported by a newer version of AIThing".to_string());
+    }
+
+    // Open the keys first so a wrong passphrase doesn't leave a half import
+    let passphrase = passphrase.filter(|passphrase| !passphrase.trim().is_empty());
+    let api_keys = match (&file.api_keys, &passphrase) {
+        (Some(secrets), Some(passphrase)) => Some(decrypt_secrets(secrets, passphrase.trim())?),
+        _ => None,
+    };
+
+    let store = app
+        .store(STORE_FILE)
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+    for (key, value) in file.store {
+        if STORE_KEYS.contains(&key.as_str()) {
+            store.set(key, value);
+        }
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store: {}", e))?;
+
+    theme::load_themes_from_store(&app, &core);
+    theme::apply_active_theme(&app, &core);
+    memory::load_memories_from_store(&app, &core);
+    profiles::load_profiles_from_store(&app, &core);
+    if let Some(settings) = file.settings {
+        crate::replace_settings(&app, &core, settings);
+    }
+
+    Ok(ImportedConfig {
+        frontend: file.frontend,
+        api_keys_locked: file.api_keys.is_some() && api_keys.is_none(),
+        api_keys,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_keys_round_trip_with_the_right_passphrase_only() {
+        let keys = BTreeMap::from([("anthropic".to_string(), "sk-ant-test".to_string())]);
+        let secrets = encrypt_secrets(&keys, "correct horse").unwrap();
+
+        assert_eq!(decrypt_secrets(&secrets, "correct horse").unwrap(), keys);
+        assert!(decrypt_secrets(&secrets, "battery staple").is_err());
+    }
+}
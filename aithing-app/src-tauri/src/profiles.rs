@@ -0,0 +1,179 @@
+//! Configuration profiles
+//!
+//! Named bundles of settings ("Work", "Personal") that can be switched in one
+//! step. A profile holds a full `AppSettings` snapshot plus frontend-owned
+//! values (selected model, persona) that the frontend restores from the
+//! `profile-changed` event.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::{AppSettings, STORE_FILE};
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub settings: AppSettings,
+    /// Values the frontend owns, such as the selected model and persona
+    #[serde(default)]
+    pub frontend: Map<String, Value>,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct ProfileState {
+    profiles: RwLock<Vec<Profile>>,
+    active: RwLock<Option<String>>,
+}
+
+// =============================================================================
+// PROFILE STORAGE
+// =============================================================================
+
+fn save_profiles_to_store<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Ok(json) = serde_json::to_value(&*core.profiles.profiles.read()) {
+            store.set("profiles", json);
+        }
+        if let Ok(json) = serde_json::to_value(&*core.profiles.active.read()) {
+            store.set("active_profile", json);
+        }
+        let _ = store.save();
+    }
+}
+
+pub fn load_profiles_from_store(app: &AppHandle, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Some(profiles_json) = store.get("profiles") {
+            if let Ok(profiles) = serde_json::from_value::<Vec<Profile>>(profiles_json) {
+                *core.profiles.profiles.write() = profiles;
+            }
+        }
+        if let Some(active_json) = store.get("active_profile") {
+            if let Ok(active) = serde_json::from_value::<Option<String>>(active_json) {
+                *core.profiles.active.write() = active;
+            }
+        }
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn list_profiles(core: State<'_, AppCore>) -> Vec<Profile> {
+    core.profiles.profiles.read().clone()
+}
+
+#[tauri::command]
+pub fn get_active_profile(core: State<'_, AppCore>) -> Option<String> {
+    core.profiles.active.read().clone()
+}
+
+/// Creates or replaces the profile called `name`. When `settings` is
+/// omitted, the current settings are captured.
+#[tauri::command]
+pub fn save_profile(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    name: String,
+    settings: Option<AppSettings>,
+    frontend: Option<Map<String, Value>>,
+) -> Result<Profile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+
+    let profile = Profile {
+        name: name.to_string(),
+        settings: settings.unwrap_or_else(|| core.settings.read().clone()),
+        frontend: frontend.unwrap_or_default(),
+    };
+
+    {
+        let mut profiles = core.profiles.profiles.write();
+        match profiles
+            .iter_mut()
+            .find(|existing| existing.name == profile.name)
+        {
+            Some(existing) => *existing = profile.clone(),
+            None => profiles.push(profile.clone()),
+        }
+    }
+    save_profiles_to_store(&app, &core);
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn delete_profile(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    name: String,
+) -> Result<(), String> {
+    {
+        let mut profiles = core.profiles.profiles.write();
+        let count = profiles.len();
+        profiles.retain(|profile| profile.name != name);
+        if profiles.len() == count {
+            return Err(format!("Profile not found: {}", name));
+        }
+    }
+    {
+        let mut active = core.profiles.active.write();
+        if active.as_deref() == Some(name.as_str()) {
+            *active = None;
+        }
+    }
+    save_profiles_to_store(&app, &core);
+    Ok(())
+}
+
+/// Replaces all settings with the profile's in one step and emits
+/// `profile-changed` so the frontend can reload settings and apply its own
+/// values.
+#[tauri::command]
+pub fn switch_profile(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    name: String,
+) -> Result<Profile, String> {
+    activate_profile(&app, &core, &name)
+}
+
+/// Does the work of `switch_profile`; also run by shortcuts bound to a
+/// profile.
+pub fn activate_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    core: &AppCore,
+    name: &str,
+) -> Result<Profile, String> {
+    let profile = core
+        .profiles
+        .profiles
+        .read()
+        .iter()
+        .find(|profile| profile.name == name)
+        .cloned()
+        .ok_or_else(|| format!("Profile not found: {}", name))?;
+
+    crate::replace_settings(app, core, profile.settings.clone());
+    *core.profiles.active.write() = Some(profile.name.clone());
+    save_profiles_to_store(app, core);
+
+    let _ = app.emit("profile-changed", &profile);
+    Ok(profile)
+}
@@ -0,0 +1,273 @@
+//! AI provider accounts
+//!
+//! Checks API keys and fetches each provider's live model catalog so the
+//! settings UI can offer real model names instead of a hard-coded list.
+//! Catalogs are cached per provider for an hour. Keys and base URLs may
+//! also come from the environment; see `secrets`.
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::{policy, secrets, vcr};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MODEL_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// OpenAI's catalog also lists embedding, audio, and image models; only these
+/// prefixes are chat models.
+const OPENAI_CHAT_PREFIXES: &[&str] = &["gpt-", "chatgpt-", "o1", "o3", "o4"];
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Anthropic,
+    OpenAI,
+    Google,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub name: String,
+    pub provider: Provider,
+    /// Input token limit, when the provider reports it
+    pub context_window: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+pub(crate) static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Default)]
+pub struct ProviderState {
+    model_cache: RwLock<HashMap<Provider, (Instant, Vec<ModelInfo>)>>,
+}
+
+// =============================================================================
+// PROVIDER REQUESTS
+// =============================================================================
+
+/// The provider's name as the frontend and policy file spell it.
+pub(crate) fn provider_id(provider: Provider) -> &'static str {
+    match provider {
+        Provider::Anthropic => "anthropic",
+        Provider::OpenAI => "openai",
+        Provider::Google => "google",
+    }
+}
+
+pub(crate) fn default_base_url(provider: Provider) -> &'static str {
+    match provider {
+        Provider::Anthropic => "https://api.anthropic.com",
+        Provider::OpenAI => "https://api.openai.com",
+        Provider::Google => "https://generativelanguage.googleapis.com",
+    }
+}
+
+/// Requests the provider's model list with `key`, from `base_url` when set.
+/// `Ok(None)` means the key was rejected.
+async fn fetch_model_list(
+    core: &AppCore,
+    provider: Provider,
+    key: &str,
+    base_url: Option<&str>,
+) -> Result<Option<Value>, String> {
+    let base_url = base_url.unwrap_or(default_base_url(provider));
+    let request = match provider {
+        Provider::Anthropic => HTTP_CLIENT
+            .get(format!("{}/v1/models?limit=1000", base_url))
+            .header("x-api-key", key)
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        Provider::OpenAI => HTTP_CLIENT
+            .get(format!("{}/v1/models", base_url))
+            .bearer_auth(key),
+        Provider::Google => HTTP_CLIENT
+            .get(format!("{}/v1beta/models?pageSize=1000", base_url))
+            .header("x-goog-api-key", key),
+    };
+
+    let request = request
+        .build()
+        .map_err(|e| format!("Failed to build provider request: {}", e))?;
+    let (status, body) = vcr::send(core, request).await?;
+
+    // Google answers an invalid key with 400 rather than 401
+    if status == reqwest::StatusCode::UNAUTHORIZED
+        || status == reqwest::StatusCode::FORBIDDEN
+        || (provider == Provider::Google && status == reqwest::StatusCode::BAD_REQUEST)
+    {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        return Err(format!("Provider returned {}", status));
+    }
+
+    serde_json::from_str::<Value>(&body)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse model list: {}", e))
+}
+
+fn parse_model_list(provider: Provider, body: &Value) -> Vec<ModelInfo> {
+    let str_field = |model: &Value, key: &str| model.get(key)?.as_str().map(str::to_string);
+
+    let mut models: Vec<ModelInfo> = match provider {
+        Provider::Anthropic => body["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|model| {
+                let id = str_field(model, "id")?;
+                Some(ModelInfo {
+                    name: str_field(model, "display_name").unwrap_or_else(|| id.clone()),
+                    id,
+                    provider,
+                    context_window: None,
+                    max_output_tokens: None,
+                })
+            })
+            .collect(),
+        Provider::OpenAI => body["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|model| str_field(model, "id"))
+            .filter(|id| {
+                OPENAI_CHAT_PREFIXES
+                    .iter()
+                    .any(|prefix| id.starts_with(prefix))
+            })
+            .map(|id| ModelInfo {
+                name: id.clone(),
+                id,
+                provider,
+                context_window: None,
+                max_output_tokens: None,
+            })
+            .collect(),
+        Provider::Google => body["models"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|model| {
+                model["supportedGenerationMethods"]
+                    .as_array()
+                    .is_some_and(|methods| methods.iter().any(|m| m == "generateContent"))
+            })
+            .filter_map(|model| {
+                let id = str_field(model, "name")?
+                    .trim_start_matches("models/")
+                    .to_string();
+                Some(ModelInfo {
+                    name: str_field(model, "displayName").unwrap_or_else(|| id.clone()),
+                    id,
+                    provider,
+                    context_window: model["inputTokenLimit"].as_u64(),
+                    max_output_tokens: model["outputTokenLimit"].as_u64(),
+                })
+            })
+            .collect(),
+    };
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models
+}
+
+pub(crate) fn cache_models(core: &AppCore, provider: Provider, models: Vec<ModelInfo>) {
+    core.providers
+        .model_cache
+        .write()
+        .insert(provider, (Instant::now(), models));
+}
+
+/// Forgets every cached catalog; the next lookup fetches again.
+pub(crate) fn clear_model_cache(core: &AppCore) {
+    core.providers.model_cache.write().clear();
+}
+
+/// Looks `id` up in the cached catalogs without a network request.
+pub fn cached_model(core: &AppCore, id: &str) -> Option<ModelInfo> {
+    core.providers
+        .model_cache
+        .read()
+        .values()
+        .flat_map(|(_, models)| models.iter())
+        .find(|model| model.id == id)
+        .cloned()
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns whether the provider accepts `key`. Network failures are errors,
+/// not a rejected key.
+#[tauri::command]
+pub async fn validate_api_key(
+    core: State<'_, AppCore>,
+    provider: Provider,
+    key: String,
+) -> Result<bool, String> {
+    policy::check_provider(&core, provider_id(provider))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Ok(false);
+    }
+
+    let base_url = secrets::resolve(&core, provider, Some(key)).base_url;
+    let Some(body) = fetch_model_list(&core, provider, key, base_url.as_deref()).await? else {
+        return Ok(false);
+    };
+
+    // The catalog came for free; keep it
+    cache_models(&core, provider, parse_model_list(provider, &body));
+    Ok(true)
+}
+
+/// Returns the provider's chat models, from cache when fresh unless `refresh`
+/// is set. Without a `key`, the environment's key is used.
+#[tauri::command]
+pub async fn list_models(
+    core: State<'_, AppCore>,
+    provider: Provider,
+    key: Option<String>,
+    refresh: Option<bool>,
+) -> Result<Vec<ModelInfo>, String> {
+    policy::check_provider(&core, provider_id(provider))?;
+    if !refresh.unwrap_or(false) {
+        if let Some((fetched_at, models)) = core.providers.model_cache.read().get(&provider) {
+            if fetched_at.elapsed() < MODEL_CACHE_TTL {
+                return Ok(models.clone());
+            }
+        }
+    }
+
+    let credentials = secrets::resolve(&core, provider, key.as_deref());
+    let key = credentials
+        .api_key
+        .ok_or_else(|| "No API key for this provider".to_string())?;
+    let body = fetch_model_list(&core, provider, &key, credentials.base_url.as_deref())
+        .await?
+        .ok_or_else(|| "API key was rejected".to_string())?;
+
+    let models = parse_model_list(provider, &body);
+    cache_models(&core, provider, models.clone());
+    Ok(models)
+}
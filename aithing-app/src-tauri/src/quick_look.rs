@@ -0,0 +1,71 @@
+//! Quick Look previews
+//!
+//! Shows attachments, exports, and generated images in the system Quick Look
+//! panel so they can be inspected without switching to another app. Only one
+//! preview is open at a time; previewing another file replaces it.
+
+use parking_lot::Mutex;
+use std::path::Path;
+use std::process::Child;
+use tauri::State;
+
+use crate::app_core::AppCore;
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct PreviewState {
+    current: Mutex<Option<Child>>,
+}
+
+// =============================================================================
+// PREVIEW
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+fn open_preview(path: &Path) -> Result<Child, String> {
+    use std::process::{Command, Stdio};
+
+    Command::new("qlmanage")
+        .arg("-p")
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to open Quick Look: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn open_preview(_path: &Path) -> Result<Child, String> {
+    Err("Quick Look is only available on macOS".to_string())
+}
+
+fn close_current_preview(core: &AppCore) {
+    if let Some(mut preview) = core.quick_look.current.lock().take() {
+        let _ = preview.kill();
+        let _ = preview.wait();
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn preview_file(core: State<'_, AppCore>, path: String) -> Result<(), String> {
+    let path = Path::new(&path);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+
+    close_current_preview(&core);
+    *core.quick_look.current.lock() = Some(open_preview(path)?);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn close_preview(core: State<'_, AppCore>) {
+    close_current_preview(&core);
+}
@@ -0,0 +1,120 @@
+//! Quiet hours
+//!
+//! A daily window during which AIThing doesn't interrupt: shortcuts keep
+//! working, but notifications, proactive suggestions, and automatic popups
+//! are held back. Subsystems ask `is_quiet_now()`; today that keeps the chord
+//! hint hidden and leaves the panel closed for quick captures. The frontend
+//! follows the `quiet-hours-changed` event to show that quiet hours are on.
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::app_core::AppCore;
+use crate::runtime;
+
+/// Quiet hours are minute-granular, so checking twice a minute is plenty.
+const QUIET_HOURS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    /// Local time in 24-hour "HH:MM" format
+    pub start: String,
+    /// Local time in 24-hour "HH:MM" format; may be earlier than `start` to
+    /// span midnight
+    pub end: String,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "08:00".to_string(),
+        }
+    }
+}
+
+// =============================================================================
+// SCHEDULE
+// =============================================================================
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+fn is_within(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        // Window spans midnight, e.g. 22:00 - 08:00
+        now >= start || now < end
+    }
+}
+
+/// True while the configured quiet hours are in effect.
+pub fn is_quiet_now(core: &AppCore) -> bool {
+    let quiet_hours = core.settings.read().quiet_hours.clone();
+    if !quiet_hours.enabled {
+        return false;
+    }
+
+    match (parse_time(&quiet_hours.start), parse_time(&quiet_hours.end)) {
+        (Some(start), Some(end)) => is_within(Local::now().time(), start, end),
+        _ => false,
+    }
+}
+
+/// Emits `quiet-hours-changed` with the new state whenever quiet hours start or
+/// end, including when the schedule itself is edited.
+pub fn start_quiet_hours_watcher(app: AppHandle) {
+    runtime::spawn(app, "quiet-hours", |app, shutdown| {
+        let mut quiet = is_quiet_now(&app.state::<AppCore>());
+
+        while shutdown.sleep(QUIET_HOURS_POLL_INTERVAL) {
+            let now_quiet = is_quiet_now(&app.state::<AppCore>());
+            if now_quiet != quiet {
+                let _ = app.emit("quiet-hours-changed", now_quiet);
+                quiet = now_quiet;
+            }
+        }
+    });
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn is_quiet_hours(core: State<'_, AppCore>) -> bool {
+    is_quiet_now(&core)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_spanning_midnight_end_at_their_end_time() {
+        let time = |value| parse_time(value).unwrap();
+        let (start, end) = (time("22:00"), time("08:00"));
+
+        for (now, quiet) in [
+            ("21:59", false),
+            ("22:00", true),
+            ("23:00", true),
+            ("07:59", true),
+            ("08:00", false),
+            ("12:00", false),
+        ] {
+            assert_eq!(is_within(time(now), start, end), quiet, "{}", now);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Actions on rendered responses
+//!
+//! Backend side of the context menu on links, images, and code blocks in chat
+//! responses: opening links outside the webview, saving images through a native
+//! save dialog, and copying code with sensible trailing newlines.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::app_core::AppCore;
+use crate::markdown;
+
+/// URL schemes a response link is allowed to open. Anything else (file://,
+/// javascript:, custom app schemes) is refused.
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Languages whose snippets are usually pasted into a terminal, where a trailing
+/// newline would run the command immediately.
+const SHELL_LANGUAGES: &[&str] = &["sh", "bash", "zsh", "fish", "shell", "console", "terminal"];
+
+/// Whether `url` is absolute and uses one of the allowed schemes.
+pub(crate) fn is_allowed_url(url: &str) -> bool {
+    tauri::Url::parse(url).is_ok_and(|parsed| ALLOWED_URL_SCHEMES.contains(&parsed.scheme()))
+}
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+/// How a copied response lands on the clipboard.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyFormat {
+    /// Markdown syntax stripped
+    Plain,
+    /// The response source as-is
+    #[default]
+    Markdown,
+    /// Rendered HTML with a plain-text fallback, for Mail, Notes, and Pages
+    Rich,
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Accepts either raw base64 or a `data:image/...;base64,` URL.
+pub(crate) fn decode_image_data(data: &str) -> Result<Vec<u8>, String> {
+    let encoded = match data.split_once(";base64,") {
+        Some((_, encoded)) => encoded,
+        None => data,
+    };
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Failed to decode image data: {}", e))
+}
+
+fn normalize_code_block(code: &str, language: Option<&str>) -> String {
+    let trimmed = code.trim_end_matches(['\n', '\r']);
+    let is_shell = language
+        .map(|language| SHELL_LANGUAGES.contains(&language.to_lowercase().as_str()))
+        .unwrap_or(false);
+
+    if is_shell || !trimmed.contains('\n') {
+        trimmed.to_string()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn open_url_in_default_browser(app: AppHandle, url: String) -> Result<(), String> {
+    let parsed = tauri::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !ALLOWED_URL_SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!("Refusing to open {} links", parsed.scheme()));
+    }
+
+    app.opener()
+        .open_url(parsed.as_str(), None::<&str>)
+        .map_err(|e| format!("Failed to open URL: {}", e))
+}
+
+/// Shows a save dialog and writes the image. Returns the saved path, or `None`
+/// if the user cancelled.
+#[tauri::command]
+pub async fn save_image_from_response(
+    app: AppHandle,
+    data: String,
+    suggested_name: String,
+) -> Result<Option<String>, String> {
+    let bytes = decode_image_data(&data)?;
+
+    let Some(path) = app
+        .dialog()
+        .file()
+        .set_file_name(&suggested_name)
+        .add_filter("Images", &["png", "jpg", "jpeg", "gif", "webp"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+
+    let path = path
+        .into_path()
+        .map_err(|e| format!("Invalid save location: {}", e))?;
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+#[tauri::command]
+pub fn copy_code_block(
+    app: AppHandle,
+    formatted: String,
+    language: Option<String>,
+) -> Result<(), String> {
+    let code = normalize_code_block(&formatted, language.as_deref());
+    app.clipboard()
+        .write_text(code)
+        .map_err(|e| format!("Failed to copy code: {}", e))
+}
+
+/// Copies a response in `format`, or in the user's default copy format.
+#[tauri::command]
+pub fn copy_response(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    text: String,
+    format: Option<CopyFormat>,
+) -> Result<(), String> {
+    let format = format.unwrap_or_else(|| core.settings.read().copy_format);
+    let clipboard = app.clipboard();
+
+    let result = match format {
+        CopyFormat::Plain => clipboard.write_text(markdown::to_plain_text(&text)),
+        CopyFormat::Markdown => clipboard.write_text(text),
+        CopyFormat::Rich => {
+            // Pasted into light documents, so use the light code theme
+            let html = markdown::render(&text, markdown::resolve_theme(Some("light")));
+            clipboard.write_html(html, Some(markdown::to_plain_text(&text)))
+        }
+    };
+    result.map_err(|e| format!("Failed to copy response: {}", e))
+}
@@ -0,0 +1,241 @@
+//! Background task supervision
+//!
+//! The backend's long-running loops (appearance, settings, and config
+//! watchers, quiet hours, Stage Manager, hot corners, memory trimming) run
+//! as supervised tasks. Each gets its own thread; a task that panics is
+//! restarted after an exponential backoff instead of silently disappearing,
+//! and every task shares one `Shutdown` signal that wakes them from their
+//! poll sleeps when the app exits, so they can stop before the process does.
+
+use parking_lot::{Condvar, Mutex};
+use serde::Serialize;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::app_core::AppCore;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A task that ran this long before panicking starts its backoff over.
+const HEALTHY_RUN: Duration = Duration::from_secs(300);
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Running,
+    /// Panicked; waiting out the backoff before restarting
+    Restarting,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskInfo {
+    pub name: String,
+    pub status: TaskStatus,
+    /// Restarts since launch
+    pub restarts: u32,
+    /// Message of the most recent panic
+    pub last_error: Option<String>,
+}
+
+/// Shared stop signal. Tasks sleep through it so that shutdown wakes them
+/// immediately.
+#[derive(Clone, Default)]
+pub struct Shutdown(Arc<(Mutex<bool>, Condvar)>);
+
+impl Shutdown {
+    /// Sleeps for `duration`, or until shutdown. Returns false once the app
+    /// is shutting down, so loops read `while shutdown.sleep(..)`.
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let (requested, condvar) = &*self.0;
+        let mut requested = requested.lock();
+        if !*requested {
+            condvar.wait_for(&mut requested, duration);
+        }
+        !*requested
+    }
+
+    fn request(&self) {
+        let (requested, condvar) = &*self.0;
+        *requested.lock() = true;
+        condvar.notify_all();
+    }
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct RuntimeState {
+    shutdown: Shutdown,
+    tasks: Mutex<Vec<(Arc<Mutex<TaskInfo>>, Option<JoinHandle<()>>)>>,
+}
+
+// =============================================================================
+// SUPERVISION
+// =============================================================================
+
+/// Delay before the `attempt`th restart in a row (1-based).
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Runs `task` on its own thread until it returns or the app shuts down,
+/// restarting it with backoff whenever it panics. The task is handed the
+/// shutdown signal and should return once `Shutdown::sleep` says so.
+pub fn spawn<R: Runtime, F>(app: AppHandle<R>, name: &str, task: F)
+where
+    F: Fn(&AppHandle<R>, &Shutdown) + Send + 'static,
+{
+    let info = Arc::new(Mutex::new(TaskInfo {
+        name: name.to_string(),
+        status: TaskStatus::Running,
+        restarts: 0,
+        last_error: None,
+    }));
+    let shutdown = app.state::<AppCore>().runtime.shutdown.clone();
+    let record = info.clone();
+    let task_app = app.clone();
+
+    let handle = std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(move || {
+            let mut attempt = 0;
+            loop {
+                record.lock().status = TaskStatus::Running;
+                let started = Instant::now();
+                let Err(panic) =
+                    std::panic::catch_unwind(AssertUnwindSafe(|| task(&task_app, &shutdown)))
+                else {
+                    break;
+                };
+
+                if started.elapsed() >= HEALTHY_RUN {
+                    attempt = 0;
+                }
+                attempt += 1;
+                {
+                    let mut info = record.lock();
+                    let message = panic_message(&*panic);
+                    eprintln!("Background task {} panicked: {}", info.name, message);
+                    info.status = TaskStatus::Restarting;
+                    info.restarts += 1;
+                    info.last_error = Some(message);
+                }
+                if !shutdown.sleep(backoff(attempt)) {
+                    break;
+                }
+            }
+            record.lock().status = TaskStatus::Stopped;
+        });
+
+    match handle {
+        Ok(handle) => app
+            .state::<AppCore>()
+            .runtime
+            .tasks
+            .lock()
+            .push((info, Some(handle))),
+        Err(e) => eprintln!("Failed to start background task {}: {}", name, e),
+    }
+}
+
+/// Signals every task to stop and waits up to `timeout` for them to finish.
+pub fn shutdown(core: &AppCore, timeout: Duration) {
+    core.runtime.shutdown.request();
+
+    let deadline = Instant::now() + timeout;
+    let handles: Vec<_> = core
+        .runtime
+        .tasks
+        .lock()
+        .iter_mut()
+        .filter_map(|(info, handle)| Some((info.clone(), handle.take()?)))
+        .collect();
+    for (info, handle) in handles {
+        while !handle.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        if handle.is_finished() {
+            let _ = handle.join();
+        } else {
+            eprintln!("Background task {} didn't stop in time", info.lock().name);
+        }
+    }
+}
+
+pub fn task_infos(core: &AppCore) -> Vec<TaskInfo> {
+    core.runtime
+        .tasks
+        .lock()
+        .iter()
+        .map(|(info, _)| info.lock().clone())
+        .collect()
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn get_background_tasks(core: State<'_, AppCore>) -> Vec<TaskInfo> {
+    task_infos(&core)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(1));
+        assert_eq!(backoff(4), Duration::from_secs(4));
+        assert_eq!(backoff(20), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn panicking_tasks_restart_and_stop_on_shutdown() {
+        let app = crate::test_support::Harness::new().launch();
+        let core = app.state::<AppCore>();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        spawn(app.handle().clone(), "flaky", move |_, shutdown| {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("first run fails");
+            }
+            while shutdown.sleep(Duration::from_secs(60)) {}
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while runs.load(Ordering::SeqCst) < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        shutdown(&core, Duration::from_secs(1));
+
+        let tasks = core.runtime.tasks.lock();
+        let info = tasks[0].0.lock();
+        assert_eq!(info.restarts, 1);
+        assert_eq!(info.last_error.as_deref(), Some("first run fails"));
+        assert_eq!(info.status, TaskStatus::Stopped);
+    }
+}
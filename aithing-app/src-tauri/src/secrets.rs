@@ -0,0 +1,172 @@
+//! Provider credentials
+//!
+//! Resolves each provider's API key and base URL. A key saved in the app wins,
+//! then the process environment, then `.env` files the user has allowed in
+//! settings, in the order listed. Apps launched from the Dock don't inherit a
+//! shell's environment, so `.env` files are the dependable route there. A
+//! base URL is only taken from the same place as the key, so a stray `.env`
+//! can't send a key saved in the app to another host.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::State;
+
+use crate::app_core::AppCore;
+use crate::providers::Provider;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    App,
+    Environment,
+    EnvFile,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub api_key: Option<String>,
+    pub source: Option<CredentialSource>,
+    /// Replaces the provider's default API origin, e.g. for a proxy
+    pub base_url: Option<String>,
+}
+
+/// What the frontend may know about a provider's credentials; never the key.
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialStatus {
+    pub provider: Provider,
+    pub source: Option<CredentialSource>,
+    pub base_url: Option<String>,
+}
+
+// =============================================================================
+// RESOLUTION
+// =============================================================================
+
+/// Variables checked for the API key, in order, and the base URL variable.
+fn variable_names(provider: Provider) -> (&'static [&'static str], &'static str) {
+    match provider {
+        Provider::Anthropic => (&["ANTHROPIC_API_KEY"], "ANTHROPIC_BASE_URL"),
+        Provider::OpenAI => (&["OPENAI_API_KEY"], "OPENAI_BASE_URL"),
+        Provider::Google => (&["GEMINI_API_KEY", "GOOGLE_API_KEY"], "GEMINI_BASE_URL"),
+    }
+}
+
+/// Reads the allowed `.env` files; a variable set in an earlier file wins.
+/// Relative paths are refused since they'd depend on the app's working
+/// directory.
+fn read_env_files(paths: &[String]) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    for path in paths {
+        let path = Path::new(path);
+        if !path.is_absolute() {
+            eprintln!("Ignoring .env path that isn't absolute: {}", path.display());
+            continue;
+        }
+        let entries = match dotenvy::from_path_iter(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        for (name, value) in entries.flatten() {
+            variables.entry(name).or_insert(value);
+        }
+    }
+    variables
+}
+
+pub fn resolve(core: &AppCore, provider: Provider, saved_key: Option<&str>) -> Credentials {
+    let env_files = read_env_files(&core.settings.read().env_files);
+    let non_empty = |value: &String| !value.trim().is_empty();
+    let from_environment = |name: &str| std::env::var(name).ok().filter(non_empty);
+    let from_env_files = |name: &str| {
+        env_files
+            .get(name)
+            .filter(|value| non_empty(value))
+            .cloned()
+    };
+    let lookup = |name: &str| {
+        from_environment(name)
+            .map(|value| (value, CredentialSource::Environment))
+            .or_else(|| from_env_files(name).map(|value| (value, CredentialSource::EnvFile)))
+    };
+
+    let (key_names, base_url_name) = variable_names(provider);
+    let key = saved_key
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| (key.to_string(), CredentialSource::App))
+        .or_else(|| key_names.iter().find_map(|name| lookup(name)));
+    let base_url = match key.as_ref().map(|(_, source)| *source) {
+        Some(CredentialSource::Environment) => from_environment(base_url_name),
+        Some(CredentialSource::EnvFile) => from_env_files(base_url_name),
+        Some(CredentialSource::App) | None => None,
+    }
+    .map(|url| url.trim().trim_end_matches('/').to_string());
+
+    Credentials {
+        source: key.as_ref().map(|(_, source)| *source),
+        api_key: key.map(|(key, _)| key.trim().to_string()),
+        base_url,
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Reports which providers have a key in the environment or an allowed `.env`
+/// file, so the frontend can use them without a key saved in the app.
+#[tauri::command]
+pub fn get_credential_sources(core: State<'_, AppCore>) -> Vec<CredentialStatus> {
+    [Provider::Anthropic, Provider::OpenAI, Provider::Google]
+        .into_iter()
+        .map(|provider| {
+            let credentials = resolve(&core, provider, None);
+            CredentialStatus {
+                provider,
+                source: credentials.source,
+                base_url: credentials.base_url,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_key_wins_over_env_files_and_ignores_their_base_url() {
+        let path = std::env::temp_dir().join(format!("aithing-{}.env", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "OPENAI_API_KEY=sk-from-file\nOPENAI_BASE_URL=https://proxy.example.com/\n",
+        )
+        .unwrap();
+        let core = AppCore::default();
+        core.settings.write().env_files = vec![path.to_string_lossy().into_owned()];
+
+        let from_file = resolve(&core, Provider::OpenAI, None);
+        let saved = resolve(&core, Provider::OpenAI, Some("sk-saved"));
+        std::fs::remove_file(&path).unwrap();
+
+        if std::env::var_os("OPENAI_API_KEY").is_none() {
+            assert_eq!(from_file.api_key.as_deref(), Some("sk-from-file"));
+            assert_eq!(from_file.source, Some(CredentialSource::EnvFile));
+            assert_eq!(
+                from_file.base_url.as_deref(),
+                Some("https://proxy.example.com")
+            );
+        }
+        assert_eq!(saved.api_key.as_deref(), Some("sk-saved"));
+        assert_eq!(saved.source, Some(CredentialSource::App));
+        assert_eq!(saved.base_url, None);
+    }
+}
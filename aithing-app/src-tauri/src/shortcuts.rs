@@ -0,0 +1,465 @@
+//! Global keyboard shortcuts
+//!
+//! Registers the panel's global shortcuts and runs their actions. Besides
+//! single shortcuts there are two-step chords: a leader shortcut (e.g.
+//! Ctrl+Shift+Space) followed by a plain key (e.g. C to capture the frontmost
+//! window). While a chord is pending its second keys are registered as
+//! global shortcuts, so they're captured whichever app is frontmost, and
+//! released again once one is pressed or the chord times out.
+//!
+//! Actions from shortcuts, chords, hot corners, and double taps all go
+//! through `dispatch`, which carries them out in the backend; the frontend
+//! only hears about results it has to display.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{
+    Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutEvent, ShortcutState as KeyState,
+};
+
+use crate::app_core::AppCore;
+use crate::{activation, active_app, profiles, quiet_hours, window_capture};
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+/// What a shortcut does. Stored as `"toggle-visibility"`, or as
+/// `{"quick-action": {"prompt": "..."}}` for actions with arguments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShortcutAction {
+    /// Show or hide the panel
+    ToggleVisibility,
+    /// Show the panel with the frontmost window attached
+    QuickCapture,
+    /// Show the panel and send `prompt`
+    QuickAction { prompt: String },
+    /// Switch to the named profile, with its persona and settings
+    SwitchProfile { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChordBinding {
+    /// Second key, e.g. "C" or "Space"
+    pub key: String,
+    pub action: ShortcutAction,
+    /// Shown in the on-screen hint
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChordSettings {
+    pub enabled: bool,
+    /// First step, e.g. "Control+Shift+Space"
+    pub leader: String,
+    /// How long the second key is waited for
+    pub timeout_ms: u64,
+    pub bindings: Vec<ChordBinding>,
+}
+
+impl Default for ChordSettings {
+    fn default() -> Self {
+        let binding = |key: &str, action: ShortcutAction, label: &str| ChordBinding {
+            key: key.to_string(),
+            action,
+            label: label.to_string(),
+        };
+        Self {
+            enabled: false,
+            leader: "Control+Shift+Space".to_string(),
+            timeout_ms: 1500,
+            bindings: vec![
+                binding("Space", ShortcutAction::ToggleVisibility, "Show or hide"),
+                binding("C", ShortcutAction::QuickCapture, "Ask about this window"),
+            ],
+        }
+    }
+}
+
+/// Payload of `chord-started`: the keys that complete the chord.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChordHint {
+    pub leader: String,
+    pub bindings: Vec<ChordBinding>,
+    pub timeout_ms: u64,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct ShortcutsState {
+    chord: Mutex<ChordMachine>,
+    /// Leader currently registered, so a changed leader can be released
+    leader: Mutex<Option<Shortcut>>,
+}
+
+/// Tracks a pending chord. Each chord gets a new generation so a timeout
+/// left over from an earlier chord can't end a later one.
+#[derive(Debug, Default)]
+struct ChordMachine {
+    generation: u64,
+    /// Second keys of the pending chord, with their actions
+    pending: Option<Vec<(Shortcut, ShortcutAction)>>,
+}
+
+enum ChordStep {
+    /// The leader was pressed; wait for a second key
+    Started(u64),
+    /// A second key completed the chord
+    Completed(ShortcutAction),
+    /// Not part of a chord
+    Ignored,
+}
+
+impl ChordMachine {
+    fn leader_pressed(&mut self, second_keys: Vec<(Shortcut, ShortcutAction)>) -> ChordStep {
+        self.generation += 1;
+        self.pending = Some(second_keys);
+        ChordStep::Started(self.generation)
+    }
+
+    fn key_pressed(&mut self, shortcut: &Shortcut) -> ChordStep {
+        let Some(keys) = self.pending.as_ref() else {
+            return ChordStep::Ignored;
+        };
+        match keys.iter().find(|(key, _)| key.id() == shortcut.id()) {
+            Some((_, action)) => {
+                let action = action.clone();
+                self.pending = None;
+                ChordStep::Completed(action)
+            }
+            None => ChordStep::Ignored,
+        }
+    }
+
+    /// Ends the chord `generation` if it's still pending; returns its keys.
+    fn expire(&mut self, generation: u64) -> Option<Vec<(Shortcut, ShortcutAction)>> {
+        if self.generation != generation {
+            return None;
+        }
+        self.pending.take()
+    }
+
+    /// Ends whatever chord is pending; returns its keys.
+    fn cancel(&mut self) -> Option<Vec<(Shortcut, ShortcutAction)>> {
+        self.pending.take()
+    }
+}
+
+// =============================================================================
+// REGISTRATION
+// =============================================================================
+
+/// Toggle visibility: Control+Option+Space (Mac) / Control+Alt+Space
+/// (Windows), or Control+Space.
+fn toggle_shortcuts() -> [Shortcut; 2] {
+    [
+        Shortcut::new(Some(Modifiers::ALT | Modifiers::CONTROL), Code::Space),
+        Shortcut::new(Some(Modifiers::CONTROL), Code::Space),
+    ]
+}
+
+fn parse_shortcut(text: &str) -> Result<Shortcut, String> {
+    text.parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", text, e))
+}
+
+/// The leader shortcut, when chords are on and it parses.
+fn chord_leader(core: &AppCore) -> Option<Shortcut> {
+    let settings = core.settings.read();
+    if !settings.chords.enabled {
+        return None;
+    }
+    match parse_shortcut(&settings.chords.leader) {
+        Ok(leader) => Some(leader),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+fn second_keys(core: &AppCore) -> Vec<(Shortcut, ShortcutAction)> {
+    core.settings
+        .read()
+        .chords
+        .bindings
+        .iter()
+        .filter_map(|binding| match parse_shortcut(&binding.key) {
+            Ok(key) => Some((key, binding.action.clone())),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Registers the toggle shortcuts and the chord leader, or unregisters them
+/// all. A leader that matches a toggle shortcut takes its place.
+pub fn set_global_shortcuts<R: Runtime>(app: &AppHandle<R>, enabled: bool) -> Result<(), String> {
+    let core = app.state::<AppCore>();
+    let global_shortcut = app.global_shortcut();
+
+    // Release everything first; the leader or bindings may have changed
+    for shortcut in toggle_shortcuts() {
+        let _ = global_shortcut.unregister(shortcut);
+    }
+    if let Some(leader) = core.shortcuts.leader.lock().take() {
+        let _ = global_shortcut.unregister(leader);
+    }
+    let pending = core.shortcuts.chord.lock().cancel();
+    if let Some(keys) = pending {
+        release_keys(app, &keys);
+        let _ = app.emit_to("main", "chord-ended", ());
+    }
+
+    if !enabled {
+        return Ok(());
+    }
+
+    let leader = chord_leader(&core);
+    let mut shortcuts: Vec<Shortcut> = toggle_shortcuts()
+        .into_iter()
+        .filter(|shortcut| leader.map_or(true, |leader| leader.id() != shortcut.id()))
+        .collect();
+    shortcuts.extend(leader);
+    *core.shortcuts.leader.lock() = leader;
+
+    global_shortcut
+        .register_multiple(shortcuts)
+        .map_err(|e| format!("Failed to register shortcuts: {}", e))
+}
+
+/// Shortcuts that should be registered but aren't, usually because another
+/// app holds them.
+pub fn unregistered<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let core = app.state::<AppCore>();
+    if !core.settings.read().shortcuts_enabled {
+        return Vec::new();
+    }
+    let global_shortcut = app.global_shortcut();
+    toggle_shortcuts()
+        .into_iter()
+        .chain(chord_leader(&core))
+        .filter(|shortcut| !global_shortcut.is_registered(*shortcut))
+        .map(|shortcut| shortcut.into_string())
+        .collect()
+}
+
+// =============================================================================
+// DISPATCH
+// =============================================================================
+
+fn begin_chord<R: Runtime>(app: &AppHandle<R>, core: &AppCore) {
+    let keys = second_keys(core);
+    let generation = match core.shortcuts.chord.lock().leader_pressed(keys.clone()) {
+        ChordStep::Started(generation) => generation,
+        _ => return,
+    };
+
+    for (key, _) in &keys {
+        // Fails harmlessly when the leader is pressed twice and the key is
+        // still registered from the first press
+        let _ = app.global_shortcut().register(*key);
+    }
+
+    let chords = core.settings.read().chords.clone();
+    // The chord still works in quiet hours, just without the hint popping up
+    if !quiet_hours::is_quiet_now(core) {
+        let _ = app.emit_to(
+            "main",
+            "chord-started",
+            ChordHint {
+                leader: chords.leader,
+                bindings: chords.bindings,
+                timeout_ms: chords.timeout_ms,
+            },
+        );
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(chords.timeout_ms));
+        let core = app.state::<AppCore>();
+        let expired = core.shortcuts.chord.lock().expire(generation);
+        if let Some(keys) = expired {
+            release_keys(&app, &keys);
+            let _ = app.emit_to("main", "chord-ended", ());
+        }
+    });
+}
+
+fn release_keys<R: Runtime>(app: &AppHandle<R>, keys: &[(Shortcut, ShortcutAction)]) {
+    for (key, _) in keys {
+        let _ = app.global_shortcut().unregister(*key);
+    }
+}
+
+fn handle_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != KeyState::Pressed {
+        return;
+    }
+    let core = app.state::<AppCore>();
+
+    // A second key completes the pending chord. The leader already passed
+    // the excluded-app check.
+    let completed = {
+        let mut chord = core.shortcuts.chord.lock();
+        let keys = chord.pending.clone().unwrap_or_default();
+        match chord.key_pressed(shortcut) {
+            ChordStep::Completed(action) => Some((keys, action)),
+            _ => None,
+        }
+    };
+    if let Some((keys, action)) = completed {
+        release_keys(app, &keys);
+        let _ = app.emit_to("main", "chord-ended", ());
+        dispatch(app, &action);
+        return;
+    }
+
+    // Stay out of excluded apps (password managers, banking, ...)
+    if active_app::is_frontmost_app_excluded(&core) {
+        return;
+    }
+
+    if chord_leader(&core).is_some_and(|leader| leader.id() == shortcut.id()) {
+        begin_chord(app, &core);
+        return;
+    }
+    if toggle_shortcuts()
+        .iter()
+        .any(|toggle| toggle.id() == shortcut.id())
+    {
+        dispatch(app, &ShortcutAction::ToggleVisibility);
+    }
+}
+
+// =============================================================================
+// ACTIONS
+// =============================================================================
+
+/// Runs `action`, timing it as an activation unless it only switches
+/// profiles. Callers check the excluded-app list first.
+///
+/// - `ToggleVisibility`: shows or hides the panel
+/// - `QuickCapture`: shows the panel (unless it's quiet hours), captures the
+///   frontmost window, and emits `window-captured` with the image (or
+///   `null`) for the frontend to attach
+/// - `QuickAction`: shows the panel and emits `quick-action` with the prompt
+/// - `SwitchProfile`: switches profiles as `switch_profile` does
+pub fn dispatch<R: Runtime>(app: &AppHandle<R>, action: &ShortcutAction) {
+    let core = app.state::<AppCore>();
+    if !matches!(action, ShortcutAction::SwitchProfile { .. }) {
+        activation::begin(&core);
+    }
+
+    let result = match action {
+        ShortcutAction::ToggleVisibility => crate::toggle_panel(app).map(|_| ()),
+        ShortcutAction::QuickCapture => {
+            // Quiet hours keep the panel closed; the capture still lands in
+            // its context for later
+            let shown = if quiet_hours::is_quiet_now(&core) {
+                activation::cancel(&core);
+                Ok(())
+            } else {
+                crate::show_panel(app)
+            };
+            shown.map(|()| {
+                // The panel never becomes frontmost, so it can be shown first
+                // and the capture, which takes a moment, done off this thread
+                let app = app.clone();
+                std::thread::spawn(move || {
+                    let capture = window_capture::capture_frontmost().unwrap_or_else(|e| {
+                        eprintln!("{}", e);
+                        None
+                    });
+                    let _ = app.emit_to("main", "window-captured", capture);
+                });
+            })
+        }
+        ShortcutAction::QuickAction { prompt } => crate::show_panel(app).map(|()| {
+            let _ = app.emit_to("main", "quick-action", prompt);
+        }),
+        ShortcutAction::SwitchProfile { name } => {
+            profiles::activate_profile(app, &core, name).map(|_| ())
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+}
+
+/// The global-shortcut plugin, wired to this module's handler.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| handle_shortcut(app, shortcut, event))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chords_complete_once_and_stale_timeouts_are_ignored() {
+        let capture = Shortcut::new(None, Code::KeyC);
+        let other = Shortcut::new(None, Code::KeyX);
+        let keys = || vec![(capture, ShortcutAction::QuickCapture)];
+        let mut machine = ChordMachine::default();
+
+        let ChordStep::Started(first) = machine.leader_pressed(keys()) else {
+            panic!("leader should start a chord");
+        };
+        assert!(matches!(machine.key_pressed(&other), ChordStep::Ignored));
+        assert!(matches!(
+            machine.key_pressed(&capture),
+            ChordStep::Completed(ShortcutAction::QuickCapture)
+        ));
+        assert!(matches!(machine.key_pressed(&capture), ChordStep::Ignored));
+
+        // A timeout from the first chord leaves the second one pending
+        let ChordStep::Started(second) = machine.leader_pressed(keys()) else {
+            panic!("leader should start a chord");
+        };
+        assert!(machine.expire(first).is_none());
+        assert!(machine.expire(second).is_some());
+    }
+
+    #[test]
+    fn actions_are_stored_as_strings_or_tagged_objects() {
+        assert_eq!(
+            serde_json::to_value(ShortcutAction::QuickCapture).unwrap(),
+            serde_json::json!("quick-capture")
+        );
+        assert_eq!(
+            serde_json::from_value::<ShortcutAction>(
+                serde_json::json!({ "switch-profile": { "name": "Work" } })
+            )
+            .unwrap(),
+            ShortcutAction::SwitchProfile {
+                name: "Work".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn default_bindings_parse() {
+        let core = AppCore::default();
+        core.settings.write().chords.enabled = true;
+
+        assert!(chord_leader(&core).is_some());
+        assert_eq!(
+            second_keys(&core).len(),
+            ChordSettings::default().bindings.len()
+        );
+    }
+}
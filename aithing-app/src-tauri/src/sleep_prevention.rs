@@ -0,0 +1,177 @@
+//! Display sleep prevention
+//!
+//! Keeps the display from sleeping, and the screen from locking, while a
+//! long task the user is watching runs. Each task holds an IOPMAssertion
+//! under its own reason, so one finishing doesn't release another's. Every
+//! change is broadcast as `sleep-prevention-changed` with the active
+//! reasons, which the panel shows as an indicator. Assertions are released
+//! on quit; macOS also drops them if the process dies.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::app_core::AppCore;
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct SleepPreventionState {
+    /// Assertion ID held for each reason
+    assertions: Mutex<HashMap<String, u32>>,
+}
+
+// =============================================================================
+// ASSERTIONS
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod iokit {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            assertion_id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(assertion_id: u32) -> i32;
+    }
+
+    const ASSERTION_LEVEL_ON: u32 = 255;
+    const IO_RETURN_SUCCESS: i32 = 0;
+
+    /// Holds off idle display sleep; the name shows in `pmset -g assertions`.
+    pub fn create(name: &str) -> Result<u32, String> {
+        let assertion_type = CFString::new("PreventUserIdleDisplaySleep");
+        let name = CFString::new(name);
+        let mut assertion_id = 0;
+        let result = unsafe {
+            IOPMAssertionCreateWithName(
+                assertion_type.as_concrete_TypeRef(),
+                ASSERTION_LEVEL_ON,
+                name.as_concrete_TypeRef(),
+                &mut assertion_id,
+            )
+        };
+        if result == IO_RETURN_SUCCESS {
+            Ok(assertion_id)
+        } else {
+            Err(format!(
+                "Failed to prevent display sleep: IOKit error {:#x}",
+                result
+            ))
+        }
+    }
+
+    pub fn release(assertion_id: u32) {
+        let result = unsafe { IOPMAssertionRelease(assertion_id) };
+        if result != IO_RETURN_SUCCESS {
+            eprintln!(
+                "Failed to release display sleep assertion: IOKit error {:#x}",
+                result
+            );
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod iokit {
+    pub fn create(_name: &str) -> Result<u32, String> {
+        Ok(0)
+    }
+
+    pub fn release(_assertion_id: u32) {}
+}
+
+fn active_reasons(core: &AppCore) -> Vec<String> {
+    let mut reasons: Vec<String> = core
+        .sleep_prevention
+        .assertions
+        .lock()
+        .keys()
+        .cloned()
+        .collect();
+    reasons.sort();
+    reasons
+}
+
+/// Prevents display sleep for `reason`, or stops preventing it. Setting
+/// the current state again does nothing.
+pub fn set<R: Runtime>(app: &AppHandle<R>, reason: &str, enabled: bool) -> Result<(), String> {
+    let core = app.state::<AppCore>();
+    {
+        let mut assertions = core.sleep_prevention.assertions.lock();
+        match (enabled, assertions.contains_key(reason)) {
+            (true, false) => {
+                let assertion_id = iokit::create(&format!("AIThing: {}", reason))?;
+                assertions.insert(reason.to_string(), assertion_id);
+            }
+            (false, true) => {
+                if let Some(assertion_id) = assertions.remove(reason) {
+                    iokit::release(assertion_id);
+                }
+            }
+            _ => return Ok(()),
+        }
+    }
+    let _ = app.emit("sleep-prevention-changed", active_reasons(&core));
+    Ok(())
+}
+
+/// Releases every assertion, for quitting.
+pub fn release_all(core: &AppCore) {
+    for (_, assertion_id) in core.sleep_prevention.assertions.lock().drain() {
+        iokit::release(assertion_id);
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+#[tauri::command]
+pub fn set_sleep_prevention(app: AppHandle, reason: String, enabled: bool) -> Result<(), String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err("A reason is required".to_string());
+    }
+    set(&app, reason, enabled)
+}
+
+/// Reasons display sleep is currently prevented for.
+#[tauri::command]
+pub fn get_sleep_prevention(core: State<'_, AppCore>) -> Vec<String> {
+    active_reasons(&core)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_reason_holds_its_own_assertion() {
+        let app = crate::test_support::Harness::new().launch();
+        let handle = app.handle();
+        let core = app.state::<AppCore>();
+
+        set(handle, "Transcribing", true).unwrap();
+        set(handle, "Agent run", true).unwrap();
+        set(handle, "Agent run", true).unwrap();
+        assert_eq!(
+            get_sleep_prevention(core.clone()),
+            vec!["Agent run", "Transcribing"]
+        );
+
+        set(handle, "Transcribing", false).unwrap();
+        assert_eq!(get_sleep_prevention(core.clone()), vec!["Agent run"]);
+
+        release_all(&core);
+        assert!(get_sleep_prevention(core.clone()).is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+//! Memory use while the panel is hidden
+//!
+//! The panel spends most of its life hidden, with the webview still holding
+//! its DOM, JS heap, and decoded images. After the panel has been hidden for
+//! a while it's trimmed: the frontend drops its rendered messages (redrawn
+//! from state on the next show) and the backend drops caches it can rebuild.
+//! In `Unload` mode the page itself is unloaded later on: the frontend hands
+//! its in-memory state to the backend, the webview navigates to a blank page
+//! so WebKit can release the content process's memory, and the next show
+//! reloads the page, which takes the state back.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, Url};
+
+use crate::app_core::AppCore;
+use crate::{providers, runtime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuspendMode {
+    /// Keep everything loaded
+    Off,
+    /// Drop caches and rendered messages
+    #[default]
+    Trim,
+    /// Trim, then unload the page
+    Unload,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuspendSettings {
+    pub mode: SuspendMode,
+    /// Hidden time before trimming
+    pub trim_after_secs: u64,
+    /// Hidden time before unloading, in `Unload` mode
+    pub unload_after_minutes: u64,
+}
+
+impl Default for SuspendSettings {
+    fn default() -> Self {
+        Self {
+            mode: SuspendMode::Trim,
+            trim_after_secs: 60,
+            unload_after_minutes: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+enum Stage {
+    #[default]
+    Awake,
+    Trimmed,
+    /// Unload requested from the frontend, which still has to hand over its
+    /// state
+    Unloading,
+    /// Navigated away from the page at this URL
+    Unloaded(Url),
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct SuspendState {
+    /// When the panel was last hidden; `None` while it's visible
+    hidden_since: Mutex<Option<Instant>>,
+    stage: Mutex<Stage>,
+    /// Frontend state handed over before an unload
+    snapshot: Mutex<Option<Value>>,
+}
+
+// =============================================================================
+// LIFECYCLE
+// =============================================================================
+
+/// The step due after the panel has been hidden for `hidden_for`, if any.
+fn next_stage(settings: &SuspendSettings, hidden_for: Duration, stage: &Stage) -> Option<Stage> {
+    let trim_after = Duration::from_secs(settings.trim_after_secs);
+    let unload_after = Duration::from_secs(settings.unload_after_minutes * 60);
+    match (settings.mode, stage) {
+        (SuspendMode::Off, _) => None,
+        (_, Stage::Awake) if hidden_for >= trim_after => Some(Stage::Trimmed),
+        (SuspendMode::Unload, Stage::Trimmed) if hidden_for >= unload_after => {
+            Some(Stage::Unloading)
+        }
+        _ => None,
+    }
+}
+
+pub fn panel_hidden(core: &AppCore) {
+    *core.suspend.hidden_since.lock() = Some(Instant::now());
+}
+
+/// Wakes the panel up before it's shown, reloading the page if it was
+/// unloaded. The frontend redraws trimmed messages on `panel-shown`.
+pub fn panel_shown<R: Runtime>(app: &AppHandle<R>, core: &AppCore) -> Result<(), String> {
+    core.suspend.hidden_since.lock().take();
+    let stage = std::mem::take(&mut *core.suspend.stage.lock());
+    let Stage::Unloaded(url) = stage else {
+        return Ok(());
+    };
+
+    app.get_webview_window("main")
+        .ok_or("Failed to get main window")?
+        .navigate(url)
+        .map_err(|e| format!("Failed to reload panel: {}", e))
+}
+
+/// Releases memory the backend can rebuild on demand.
+fn trim_backend(core: &AppCore) {
+    providers::clear_model_cache(core);
+
+    #[cfg(target_os = "macos")]
+    {
+        extern "C" {
+            fn malloc_zone_pressure_relief(zone: *mut std::ffi::c_void, goal: usize) -> usize;
+        }
+        // Hand freed pages in every malloc zone back to the system
+        unsafe { malloc_zone_pressure_relief(std::ptr::null_mut(), 0) };
+    }
+}
+
+/// Trims and unloads the panel as it stays hidden.
+pub fn start_suspend_watcher(app: AppHandle) {
+    runtime::spawn(app, "suspend", |app, shutdown| {
+        while shutdown.sleep(POLL_INTERVAL) {
+            let core = app.state::<AppCore>();
+            let Some(hidden_since) = *core.suspend.hidden_since.lock() else {
+                continue;
+            };
+            let settings = core.settings.read().suspend;
+            let next = {
+                let mut stage = core.suspend.stage.lock();
+                let next = next_stage(&settings, hidden_since.elapsed(), &stage);
+                if let Some(next) = &next {
+                    *stage = next.clone();
+                }
+                next
+            };
+
+            match next {
+                Some(Stage::Trimmed) => {
+                    trim_backend(&core);
+                    let _ = app.emit_to("main", "memory-trim", ());
+                }
+                // The frontend answers with unload_webview
+                Some(Stage::Unloading) => {
+                    let _ = app.emit_to("main", "webview-unload", ());
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Keeps `snapshot` of the frontend's state and unloads the page. Does
+/// nothing if the panel was shown since the unload was requested.
+#[tauri::command]
+pub fn unload_webview(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    snapshot: Value,
+) -> Result<(), String> {
+    let mut stage = core.suspend.stage.lock();
+    if *stage != Stage::Unloading {
+        return Ok(());
+    }
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let url = window
+        .url()
+        .map_err(|e| format!("Failed to get panel URL: {}", e))?;
+    *core.suspend.snapshot.lock() = Some(snapshot);
+    window
+        .navigate(Url::parse("about:blank").expect("valid URL"))
+        .map_err(|e| format!("Failed to unload panel: {}", e))?;
+    *stage = Stage::Unloaded(url);
+    Ok(())
+}
+
+/// The state handed over before the last unload, once.
+#[tauri::command]
+pub fn take_webview_snapshot(core: State<'_, AppCore>) -> Option<Value> {
+    core.suspend.snapshot.lock().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_panel_is_trimmed_then_unloaded() {
+        let settings = SuspendSettings {
+            mode: SuspendMode::Unload,
+            ..SuspendSettings::default()
+        };
+        let minutes = |n| Duration::from_secs(n * 60);
+
+        assert_eq!(
+            next_stage(&settings, Duration::from_secs(10), &Stage::Awake),
+            None
+        );
+        assert_eq!(
+            next_stage(&settings, minutes(2), &Stage::Awake),
+            Some(Stage::Trimmed)
+        );
+        assert_eq!(next_stage(&settings, minutes(2), &Stage::Trimmed), None);
+        assert_eq!(
+            next_stage(&settings, minutes(10), &Stage::Trimmed),
+            Some(Stage::Unloading)
+        );
+        assert_eq!(next_stage(&settings, minutes(20), &Stage::Unloading), None);
+    }
+
+    #[test]
+    fn trim_mode_never_unloads() {
+        let settings = SuspendSettings::default();
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        assert_eq!(next_stage(&settings, day, &Stage::Trimmed), None);
+        assert_eq!(
+            next_stage(
+                &SuspendSettings {
+                    mode: SuspendMode::Off,
+                    ..settings
+                },
+                day,
+                &Stage::Awake
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Read-only access to macOS user defaults
+//!
+//! Thin wrapper over CFPreferences for reading system settings the backend
+//! reacts to (appearance, accessibility, window manager). Values are
+//! re-synchronized on every read so changes made in System Settings are seen
+//! without restarting.
+
+use core_foundation::base::{CFType, CFTypeRef, TCFType};
+use core_foundation::boolean::CFBoolean;
+use core_foundation::number::CFNumber;
+use core_foundation::string::{CFString, CFStringRef};
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    static kCFPreferencesAnyApplication: CFStringRef;
+    fn CFPreferencesAppSynchronize(application_id: CFStringRef) -> u8;
+    fn CFPreferencesCopyAppValue(key: CFStringRef, application_id: CFStringRef) -> CFTypeRef;
+}
+
+/// Copies `key` from `domain`, or from the global domain (`NSGlobalDomain`)
+/// when `domain` is `None`.
+fn copy_value(domain: Option<&str>, key: &str) -> Option<CFType> {
+    let domain = domain.map(CFString::new);
+    let application_id = match &domain {
+        Some(domain) => domain.as_concrete_TypeRef(),
+        None => unsafe { kCFPreferencesAnyApplication },
+    };
+    let key = CFString::new(key);
+
+    unsafe {
+        CFPreferencesAppSynchronize(application_id);
+        let value = CFPreferencesCopyAppValue(key.as_concrete_TypeRef(), application_id);
+        if value.is_null() {
+            None
+        } else {
+            Some(CFType::wrap_under_create_rule(value))
+        }
+    }
+}
+
+pub fn string_value(domain: Option<&str>, key: &str) -> Option<String> {
+    copy_value(domain, key)?
+        .downcast::<CFString>()
+        .map(|value| value.to_string())
+}
+
+pub fn int_value(domain: Option<&str>, key: &str) -> Option<i64> {
+    copy_value(domain, key)?.downcast::<CFNumber>()?.to_i64()
+}
+
+pub fn bool_value(domain: Option<&str>, key: &str) -> Option<bool> {
+    let value = copy_value(domain, key)?;
+    match value.downcast::<CFBoolean>() {
+        Some(value) => Some(value.into()),
+        // Some writers store booleans as 0/1 numbers
+        None => value
+            .downcast::<CFNumber>()?
+            .to_i64()
+            .map(|value| value != 0),
+    }
+}
@@ -0,0 +1,51 @@
+//! Headless test harness
+//!
+//! Builds the app on Tauri's mock runtime with the store plugin and an
+//! `AppCore`, but no panel, windows, or global shortcuts, so backend logic can
+//! be tested on Linux CI. Each `Harness` gets its own app identifier, and so
+//! its own store file; launching it again simulates a restart.
+
+use std::sync::Once;
+use tauri::test::{mock_builder, mock_context, noop_assets, MockRuntime};
+use tauri::{App, Manager};
+
+use crate::app_core::AppCore;
+
+static ISOLATE_DATA_DIR: Once = Once::new();
+
+/// Points the app data directory at a scratch directory so tests never touch
+/// the real store.
+fn isolate_data_dir() {
+    ISOLATE_DATA_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("aithing-tests-{}", std::process::id()));
+        std::env::set_var("XDG_DATA_HOME", &dir);
+        std::env::set_var("HOME", &dir);
+    });
+}
+
+pub struct Harness {
+    identifier: String,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        isolate_data_dir();
+        Self {
+            identifier: format!("com.aithing.test.{}", uuid::Uuid::new_v4()),
+        }
+    }
+
+    /// Builds a fresh app with an empty `AppCore` on this harness's data
+    /// directory. Nothing is loaded from the store until the test asks.
+    pub fn launch(&self) -> App<MockRuntime> {
+        let mut context = mock_context(noop_assets());
+        context.config_mut().identifier = self.identifier.clone();
+
+        let app = mock_builder()
+            .plugin(tauri_plugin_store::Builder::new().build())
+            .build(context)
+            .expect("failed to build mock app");
+        app.manage(AppCore::default());
+        app
+    }
+}
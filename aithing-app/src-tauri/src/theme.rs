@@ -0,0 +1,259 @@
+//! Backend-managed themes
+//!
+//! A theme bundles the CSS tokens the frontend renders with (colors, blur,
+//! corner radius, font) and the native window material that CSS alone can't
+//! provide. Custom themes and the active theme name are persisted in the app
+//! store alongside settings.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::window::Effect;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::app_core::AppCore;
+use crate::appearance;
+use crate::STORE_FILE;
+
+const DEFAULT_THEME: &str = "dark";
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    /// CSS custom properties without the leading `--`, e.g. `bg-primary`.
+    pub colors: BTreeMap<String, String>,
+    pub blur_radius: f64,
+    pub corner_radius: f64,
+    pub font_family: String,
+    pub font_size: f64,
+    /// Native material drawn behind the webview (NSVisualEffectView on macOS).
+    pub material: Option<Effect>,
+}
+
+// =============================================================================
+// BUILT-IN THEMES
+// =============================================================================
+
+const SYSTEM_FONT: &str =
+    "-apple-system, BlinkMacSystemFont, 'SF Pro Text', 'Segoe UI', Roboto, Helvetica, Arial, sans-serif";
+
+fn colors(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+    entries
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme {
+            name: "dark".to_string(),
+            colors: colors(&[
+                ("bg-primary", "rgba(0, 0, 0, 0.95)"),
+                ("bg-secondary", "rgba(255, 255, 255, 0.1)"),
+                ("bg-tertiary", "rgba(255, 255, 255, 0.05)"),
+                ("bg-hover", "rgba(255, 255, 255, 0.15)"),
+                ("bg-active", "rgba(255, 255, 255, 0.2)"),
+                ("text-primary", "rgba(255, 255, 255, 0.9)"),
+                ("text-secondary", "rgba(255, 255, 255, 0.6)"),
+                ("text-muted", "rgba(255, 255, 255, 0.4)"),
+                ("border-color", "rgba(255, 255, 255, 0.1)"),
+                ("accent-color", "#007AFF"),
+            ]),
+            blur_radius: 50.0,
+            corner_radius: 24.0,
+            font_family: SYSTEM_FONT.to_string(),
+            font_size: 14.0,
+            material: None,
+        },
+        Theme {
+            name: "light".to_string(),
+            colors: colors(&[
+                ("bg-primary", "rgba(255, 255, 255, 0.95)"),
+                ("bg-secondary", "rgba(0, 0, 0, 0.08)"),
+                ("bg-tertiary", "rgba(0, 0, 0, 0.04)"),
+                ("bg-hover", "rgba(0, 0, 0, 0.12)"),
+                ("bg-active", "rgba(0, 0, 0, 0.16)"),
+                ("text-primary", "rgba(0, 0, 0, 0.9)"),
+                ("text-secondary", "rgba(0, 0, 0, 0.6)"),
+                ("text-muted", "rgba(0, 0, 0, 0.4)"),
+                ("border-color", "rgba(0, 0, 0, 0.1)"),
+                ("accent-color", "#007AFF"),
+            ]),
+            blur_radius: 50.0,
+            corner_radius: 24.0,
+            font_family: SYSTEM_FONT.to_string(),
+            font_size: 14.0,
+            material: None,
+        },
+        Theme {
+            name: "glass".to_string(),
+            colors: colors(&[
+                ("bg-primary", "rgba(0, 0, 0, 0.35)"),
+                ("bg-secondary", "rgba(255, 255, 255, 0.12)"),
+                ("bg-tertiary", "rgba(255, 255, 255, 0.06)"),
+                ("bg-hover", "rgba(255, 255, 255, 0.18)"),
+                ("bg-active", "rgba(255, 255, 255, 0.24)"),
+                ("text-primary", "rgba(255, 255, 255, 0.92)"),
+                ("text-secondary", "rgba(255, 255, 255, 0.65)"),
+                ("text-muted", "rgba(255, 255, 255, 0.45)"),
+                ("border-color", "rgba(255, 255, 255, 0.15)"),
+                ("accent-color", "#007AFF"),
+            ]),
+            blur_radius: 0.0,
+            corner_radius: 24.0,
+            font_family: SYSTEM_FONT.to_string(),
+            font_size: 14.0,
+            material: Some(Effect::HudWindow),
+        },
+    ]
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+pub struct ThemeState {
+    active: RwLock<String>,
+    custom: RwLock<BTreeMap<String, Theme>>,
+}
+
+impl Default for ThemeState {
+    fn default() -> Self {
+        Self {
+            active: RwLock::new(DEFAULT_THEME.to_string()),
+            custom: RwLock::new(BTreeMap::new()),
+        }
+    }
+}
+
+/// Custom themes take precedence over built-ins with the same name.
+fn find_theme(core: &AppCore, name: &str) -> Option<Theme> {
+    if let Some(theme) = core.theme.custom.read().get(name) {
+        return Some(theme.clone());
+    }
+    builtin_themes()
+        .into_iter()
+        .find(|theme| theme.name == name)
+}
+
+fn active_theme(core: &AppCore) -> Theme {
+    let name = core.theme.active.read().clone();
+    find_theme(core, &name)
+        .unwrap_or_else(|| find_theme(core, DEFAULT_THEME).expect("default theme is built in"))
+}
+
+// =============================================================================
+// THEME STORAGE
+// =============================================================================
+
+fn save_themes_to_store(app: &AppHandle, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Ok(json) = serde_json::to_value(&*core.theme.custom.read()) {
+            store.set("themes", json);
+        }
+        store.set("active_theme", core.theme.active.read().clone());
+        let _ = store.save();
+    }
+}
+
+pub fn load_themes_from_store(app: &AppHandle, core: &AppCore) {
+    if let Ok(store) = app.store(STORE_FILE) {
+        if let Some(themes_json) = store.get("themes") {
+            if let Ok(themes) = serde_json::from_value::<BTreeMap<String, Theme>>(themes_json) {
+                *core.theme.custom.write() = themes;
+            }
+        }
+        if let Some(serde_json::Value::String(name)) = store.get("active_theme") {
+            *core.theme.active.write() = name;
+        }
+    }
+}
+
+// =============================================================================
+// NATIVE EFFECTS
+// =============================================================================
+
+fn apply_theme(app: &AppHandle, theme: &Theme) -> Result<(), String> {
+    appearance::apply_material(app, theme.material, theme.corner_radius)
+}
+
+/// Applies the persisted active theme's native effects on startup.
+pub fn apply_active_theme(app: &AppHandle, core: &AppCore) {
+    if let Err(e) = apply_theme(app, &active_theme(core)) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Replaces the active theme's native material (and optionally its corner
+/// radius), keeping every other token.
+pub fn set_active_material(
+    app: &AppHandle,
+    core: &AppCore,
+    material: Option<Effect>,
+    corner_radius: Option<f64>,
+) -> Result<(), String> {
+    let mut theme = active_theme(core);
+    theme.material = material;
+    if let Some(corner_radius) = corner_radius {
+        theme.corner_radius = corner_radius;
+    }
+    activate_theme(app, core, theme)
+}
+
+/// Saves `theme` under its name, makes it active, and applies it.
+fn activate_theme(app: &AppHandle, core: &AppCore, theme: Theme) -> Result<(), String> {
+    if theme.name.trim().is_empty() {
+        return Err("Theme name cannot be empty".to_string());
+    }
+
+    apply_theme(app, &theme)?;
+
+    core.theme
+        .custom
+        .write()
+        .insert(theme.name.clone(), theme.clone());
+    *core.theme.active.write() = theme.name.clone();
+    save_themes_to_store(app, core);
+
+    let _ = app.emit("theme-changed", &theme);
+    Ok(())
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Returns the named theme, or the active theme when no name is given.
+#[tauri::command]
+pub fn get_theme(core: State<'_, AppCore>, name: Option<String>) -> Result<Theme, String> {
+    match name {
+        Some(name) => find_theme(&core, &name).ok_or_else(|| format!("Unknown theme: {}", name)),
+        None => Ok(active_theme(&core)),
+    }
+}
+
+#[tauri::command]
+pub fn list_themes(core: State<'_, AppCore>) -> Vec<String> {
+    let mut names: Vec<String> = builtin_themes()
+        .into_iter()
+        .map(|theme| theme.name)
+        .collect();
+    for name in core.theme.custom.read().keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+/// Saves `theme` under its name, makes it active, and applies it.
+#[tauri::command]
+pub fn set_theme(app: AppHandle, core: State<'_, AppCore>, theme: Theme) -> Result<(), String> {
+    activate_theme(&app, &core, theme)
+}
@@ -0,0 +1,243 @@
+//! Provider traffic recording
+//!
+//! Records provider responses to a cassette file and replays them later in
+//! the same order, so a bug in how a response is handled can be reproduced
+//! without the provider, the account, or the network. Only the method, URL,
+//! status, and body are kept; headers, where the API keys travel, never are.
+//! Cassettes live in the app data directory; the webview only names them.
+
+use parking_lot::{Mutex, RwLock};
+use reqwest::{Request, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::app_core::AppCore;
+use crate::providers::HTTP_CLIENT;
+
+/// Query parameters some providers accept keys in.
+const SECRET_QUERY_PARAMS: &[&str] = &["key", "api_key", "access_token"];
+/// Directory under the app data directory that holds cassettes.
+const CASSETTE_DIR: &str = "cassettes";
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "path", rename_all = "lowercase")]
+pub enum ReplayMode {
+    #[default]
+    Off,
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    status: u16,
+    body: String,
+}
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct VcrState {
+    mode: RwLock<ReplayMode>,
+    /// Recorded so far, or still to be replayed
+    cassette: Mutex<Vec<Interaction>>,
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+fn redacted_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if SECRET_QUERY_PARAMS.contains(&name.as_ref()) {
+                "REDACTED".to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    if !query.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(query);
+    }
+    url.to_string()
+}
+
+/// The file name `name` is made of, if it's a bare name that can't point
+/// outside the cassette directory.
+fn cassette_file_name(name: &Path) -> Option<&OsStr> {
+    let mut components = name.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file_name)), None) => Some(file_name),
+        _ => None,
+    }
+}
+
+fn cassette_path(app: &AppHandle, name: &Path) -> Result<PathBuf, String> {
+    let file_name = cassette_file_name(name)
+        .ok_or_else(|| format!("Invalid cassette name: {}", name.display()))?;
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to locate app data: {}", e))?
+        .join(CASSETTE_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir.join(file_name))
+}
+
+fn read_cassette(path: &Path) -> Result<Vec<Interaction>, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read cassette {}: {}", path.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse cassette {}: {}", path.display(), e))
+}
+
+fn write_cassette(path: &Path, interactions: &[Interaction]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(interactions)
+        .map_err(|e| format!("Failed to serialize cassette: {}", e))?;
+    std::fs::write(path, json)
+        .map_err(|e| format!("Failed to write cassette {}: {}", path.display(), e))
+}
+
+/// Takes the earliest recorded response to `method` `url`, so repeated
+/// requests replay in the order they were recorded.
+fn take_recorded(core: &AppCore, method: &str, url: &str) -> Option<Interaction> {
+    let mut cassette = core.vcr.cassette.lock();
+    let index = cassette
+        .iter()
+        .position(|interaction| interaction.method == method && interaction.url == url)?;
+    Some(cassette.remove(index))
+}
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+/// Sends `request` through the shared client, or answers it from the
+/// cassette, depending on the replay mode. Returns the status and body.
+pub async fn send(core: &AppCore, request: Request) -> Result<(StatusCode, String), String> {
+    let method = request.method().to_string();
+    let url = redacted_url(request.url());
+    let mode = core.vcr.mode.read().clone();
+
+    if let ReplayMode::Replay(_) = mode {
+        let interaction = take_recorded(core, &method, &url)
+            .ok_or_else(|| format!("No recorded response for {} {}", method, url))?;
+        let status = StatusCode::from_u16(interaction.status)
+            .map_err(|e| format!("Invalid recorded status: {}", e))?;
+        return Ok((status, interaction.body));
+    }
+
+    let response = HTTP_CLIENT
+        .execute(request)
+        .await
+        .map_err(|e| format!("Failed to reach provider: {}", e))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read provider response: {}", e))?;
+
+    if let ReplayMode::Record(path) = mode {
+        let mut cassette = core.vcr.cassette.lock();
+        cassette.push(Interaction {
+            method,
+            url,
+            status: status.as_u16(),
+            body: body.clone(),
+        });
+        if let Err(e) = write_cassette(&path, &cassette) {
+            eprintln!("{}", e);
+        }
+    }
+    Ok((status, body))
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Starts recording provider traffic to a new cassette, replays an existing
+/// one, or turns both off. The path is a file name in the cassette
+/// directory. Recording overwrites the file.
+#[tauri::command]
+pub fn set_replay_mode(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    mode: ReplayMode,
+) -> Result<(), String> {
+    let mode = match mode {
+        ReplayMode::Off => ReplayMode::Off,
+        ReplayMode::Record(name) => ReplayMode::Record(cassette_path(&app, &name)?),
+        ReplayMode::Replay(name) => ReplayMode::Replay(cassette_path(&app, &name)?),
+    };
+    let cassette = match &mode {
+        ReplayMode::Off => Vec::new(),
+        ReplayMode::Record(path) => {
+            write_cassette(path, &[])?;
+            Vec::new()
+        }
+        ReplayMode::Replay(path) => read_cassette(path)?,
+    };
+
+    *core.vcr.cassette.lock() = cassette;
+    *core.vcr.mode.write() = mode;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_in_query_strings_are_redacted() {
+        let url =
+            reqwest::Url::parse("https://example.com/v1/models?key=secret&pageSize=10").unwrap();
+        assert_eq!(
+            redacted_url(&url),
+            "https://example.com/v1/models?key=REDACTED&pageSize=10"
+        );
+    }
+
+    #[test]
+    fn cassettes_are_named_not_pathed() {
+        assert_eq!(
+            cassette_file_name(Path::new("session.json")),
+            Some(OsStr::new("session.json"))
+        );
+        for name in ["", ".", "..", "../x.json", "/etc/passwd", "dir/x.json"] {
+            assert_eq!(cassette_file_name(Path::new(name)), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn repeated_requests_replay_in_recorded_order() {
+        let core = AppCore::default();
+        let interaction = |body: &str| Interaction {
+            method: "GET".to_string(),
+            url: "https://example.com/v1/models".to_string(),
+            status: 200,
+            body: body.to_string(),
+        };
+        *core.vcr.cassette.lock() = vec![interaction("first"), interaction("second")];
+
+        let take = || take_recorded(&core, "GET", "https://example.com/v1/models").map(|i| i.body);
+        assert_eq!(take().as_deref(), Some("first"));
+        assert_eq!(take().as_deref(), Some("second"));
+        assert_eq!(take(), None);
+    }
+}
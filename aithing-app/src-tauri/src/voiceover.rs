@@ -0,0 +1,182 @@
+//! VoiceOver support
+//!
+//! Gives the panel a title and role VoiceOver can read (a borderless
+//! nonactivating panel otherwise reads as an untitled window), and, when the
+//! user opts in, announces finished responses so they don't have to keep
+//! checking whether the model is still writing.
+
+use tauri::{AppHandle, Runtime, State};
+
+use crate::app_core::AppCore;
+use crate::i18n::t;
+
+/// `NSAccessibilityPriorityHigh`: interrupts other speech, which suits a
+/// response the user is waiting on.
+#[cfg(target_os = "macos")]
+const ANNOUNCEMENT_PRIORITY_HIGH: i64 = 90;
+
+// =============================================================================
+// PANEL LABELS
+// =============================================================================
+
+/// Sets the main panel's accessibility title and role. Called after the
+/// window is converted to an NSPanel, and again when the locale changes.
+#[cfg(target_os = "macos")]
+pub fn label_panel<R: Runtime>(app: &AppHandle<R>, core: &AppCore) -> Result<(), String> {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::CFString;
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+    use tauri::Manager;
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Failed to get main window")?;
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| format!("Failed to get native window: {}", e))?
+        as *mut AnyObject;
+
+    // CFString is toll-free bridged to NSString
+    let title = CFString::new(&t(core, "panel-accessibility-title", &[]));
+    let role = CFString::new("AXWindow");
+    let subrole = CFString::new("AXFloatingWindow");
+    let as_object = |string: &CFString| string.as_concrete_TypeRef() as *const AnyObject;
+
+    unsafe {
+        let _: () = msg_send![ns_window, setAccessibilityTitle: as_object(&title)];
+        let _: () = msg_send![ns_window, setAccessibilityLabel: as_object(&title)];
+        let _: () = msg_send![ns_window, setAccessibilityRole: as_object(&role)];
+        let _: () = msg_send![ns_window, setAccessibilitySubrole: as_object(&subrole)];
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn label_panel<R: Runtime>(_app: &AppHandle<R>, _core: &AppCore) -> Result<(), String> {
+    Ok(())
+}
+
+// =============================================================================
+// ANNOUNCEMENTS
+// =============================================================================
+
+/// Asks VoiceOver to speak `text`. Does nothing when VoiceOver is off.
+#[cfg(target_os = "macos")]
+fn post_announcement<R: Runtime>(app: &AppHandle<R>, text: String) -> Result<(), String> {
+    app.run_on_main_thread(move || {
+        use core_foundation::base::{CFType, TCFType};
+        use core_foundation::dictionary::{CFDictionary, CFDictionaryRef};
+        use core_foundation::number::CFNumber;
+        use core_foundation::string::{CFString, CFStringRef};
+        use objc2::runtime::AnyObject;
+        use objc2::{class, msg_send};
+
+        #[link(name = "AppKit", kind = "framework")]
+        extern "C" {
+            fn NSAccessibilityPostNotificationWithUserInfo(
+                element: *mut AnyObject,
+                notification: CFStringRef,
+                user_info: CFDictionaryRef,
+            );
+        }
+
+        let user_info = CFDictionary::<CFString, CFType>::from_CFType_pairs(&[
+            (
+                CFString::new("AXAnnouncementKey"),
+                CFString::new(&text).as_CFType(),
+            ),
+            (
+                CFString::new("AXPriorityKey"),
+                CFNumber::from(ANNOUNCEMENT_PRIORITY_HIGH).as_CFType(),
+            ),
+        ]);
+        let notification = CFString::new("AXAnnouncementRequested");
+
+        unsafe {
+            // Announcements are posted on the app, not a window, so they're
+            // spoken even while the panel doesn't have focus
+            let application: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            NSAccessibilityPostNotificationWithUserInfo(
+                application,
+                notification.as_concrete_TypeRef(),
+                user_info.as_concrete_TypeRef(),
+            );
+        }
+    })
+    .map_err(|e| format!("Failed to post announcement: {}", e))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn post_announcement<R: Runtime>(_app: &AppHandle<R>, _text: String) -> Result<(), String> {
+    Ok(())
+}
+
+/// The spoken text for a finished response: a short notice, then the start of
+/// the response so the user can tell whether to read on.
+fn announcement_text(core: &AppCore, preview: Option<&str>) -> String {
+    let notice = t(core, "response-finished-announcement", &[]);
+    let preview = preview
+        .map(|preview| preview.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|preview| !preview.is_empty());
+
+    match preview {
+        Some(preview) => {
+            // Up to the first word ending a sentence, so "2.5" doesn't cut it
+            let sentence = preview
+                .match_indices(|c| matches!(c, '.' | '!' | '?'))
+                .map(|(index, _)| index + 1)
+                .find(|end| preview[*end..].is_empty() || preview[*end..].starts_with(' '))
+                .map_or(preview.as_str(), |end| &preview[..end]);
+            format!("{} {}", notice, sentence)
+        }
+        None => notice,
+    }
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Turns VoiceOver announcements for finished responses on or off.
+#[tauri::command]
+pub fn set_accessibility_announcements(app: AppHandle, core: State<'_, AppCore>, enabled: bool) {
+    let mut settings = core.settings.read().clone();
+    settings.accessibility_announcements = enabled;
+    crate::replace_settings(&app, &core, settings);
+}
+
+/// Announces that a response finished, with its first sentence when
+/// `preview` is given. Called by the frontend once a response is complete;
+/// does nothing unless announcements are enabled.
+#[tauri::command]
+pub fn announce_response_finished(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    preview: Option<String>,
+) -> Result<(), String> {
+    if !core.settings.read().accessibility_announcements {
+        return Ok(());
+    }
+    post_announcement(&app, announcement_text(&core, preview.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn announcement_reads_the_first_sentence_only() {
+        let core = AppCore::default();
+        core.settings.write().locale = Some("en-US".to_string());
+
+        assert_eq!(
+            announcement_text(
+                &core,
+                Some("Paris has 2.1 million people.\n\nIt is the capital.")
+            ),
+            "Response ready. Paris has 2.1 million people."
+        );
+        assert_eq!(announcement_text(&core, Some("  ")), "Response ready.");
+    }
+}
@@ -0,0 +1,179 @@
+//! Frontmost window capture
+//!
+//! Grabs an image of just the window the user is working in, never the whole
+//! screen, so "what am I looking at" questions can be answered visually. The
+//! AIThing panel never becomes the frontmost app, so it is never captured.
+//! Excluded apps are never captured either.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::active_app;
+use crate::app_core::AppCore;
+
+/// Longest edge of the attached image. Enough to read UI text while keeping
+/// the upload small.
+#[cfg(target_os = "macos")]
+const MAX_CAPTURE_DIMENSION: u32 = 1280;
+
+// =============================================================================
+// DATA TYPES
+// =============================================================================
+
+/// Shaped like the frontend's image context items.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowCapture {
+    pub name: String,
+    pub media: String,
+    /// Base64-encoded image data
+    pub image: String,
+}
+
+// =============================================================================
+// MACOS WINDOW LIST
+// =============================================================================
+
+#[cfg(target_os = "macos")]
+mod ffi {
+    use core_foundation::array::CFArrayRef;
+
+    pub const K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY: u32 = 1 << 0;
+    pub const K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        pub fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> CFArrayRef;
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn dictionary_value(
+    window: &core_foundation::dictionary::CFDictionary,
+    key: &str,
+) -> Option<core_foundation::base::CFType> {
+    use core_foundation::base::{CFType, CFTypeRef, TCFType};
+    use core_foundation::string::CFString;
+
+    let key = CFString::new(key);
+    let value = window.find(key.as_CFTypeRef())?;
+    Some(unsafe { CFType::wrap_under_get_rule(*value as CFTypeRef) })
+}
+
+/// Finds the frontmost normal-layer window owned by `pid`. The window list is
+/// ordered front to back, so the first match is the one the user sees.
+#[cfg(target_os = "macos")]
+fn frontmost_window(pid: i32) -> Option<(u32, String)> {
+    use core_foundation::array::CFArray;
+    use core_foundation::base::TCFType;
+    use core_foundation::dictionary::CFDictionary;
+    use core_foundation::number::CFNumber;
+    use core_foundation::string::CFString;
+
+    let windows: CFArray<CFDictionary> = unsafe {
+        let list = ffi::CGWindowListCopyWindowInfo(
+            ffi::K_CG_WINDOW_LIST_OPTION_ON_SCREEN_ONLY
+                | ffi::K_CG_WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS,
+            0,
+        );
+        if list.is_null() {
+            return None;
+        }
+        CFArray::wrap_under_create_rule(list)
+    };
+
+    let number = |window: &CFDictionary, key: &str| {
+        dictionary_value(window, key)?
+            .downcast::<CFNumber>()?
+            .to_i64()
+    };
+
+    windows.iter().find_map(|window| {
+        if number(&window, "kCGWindowOwnerPID")? != i64::from(pid)
+            || number(&window, "kCGWindowLayer")? != 0
+        {
+            return None;
+        }
+        let id = number(&window, "kCGWindowNumber")? as u32;
+        let owner = dictionary_value(&window, "kCGWindowOwnerName")
+            .and_then(|value| value.downcast::<CFString>())
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| "Window".to_string());
+        Some((id, owner))
+    })
+}
+
+// =============================================================================
+// CAPTURE
+// =============================================================================
+
+/// Captures window `id` without its shadow and scales it down with `sips`.
+#[cfg(target_os = "macos")]
+fn capture_window(id: u32) -> Result<Vec<u8>, String> {
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("aithing-capture-{}.jpg", uuid::Uuid::new_v4()));
+
+    let captured = Command::new("screencapture")
+        .args(["-x", "-o", "-t", "jpg"])
+        .arg(format!("-l{}", id))
+        .arg(&path)
+        .status()
+        .map_err(|e| format!("Failed to run screencapture: {}", e))?;
+
+    let result = if !captured.success() || !path.exists() {
+        Err("Window capture failed (is Screen Recording allowed?)".to_string())
+    } else {
+        Command::new("sips")
+            .arg("-Z")
+            .arg(MAX_CAPTURE_DIMENSION.to_string())
+            .arg(&path)
+            .output()
+            .map_err(|e| format!("Failed to resize capture: {}", e))
+            .and_then(|_| {
+                std::fs::read(&path).map_err(|e| format!("Failed to read capture: {}", e))
+            })
+    };
+
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+#[cfg(target_os = "macos")]
+pub fn capture_frontmost() -> Result<Option<WindowCapture>, String> {
+    use base64::Engine;
+
+    let Some(pid) = active_app::frontmost_pid() else {
+        return Ok(None);
+    };
+    let Some((id, owner)) = frontmost_window(pid) else {
+        return Ok(None);
+    };
+
+    let bytes = capture_window(id)?;
+    Ok(Some(WindowCapture {
+        name: owner,
+        media: "image/jpeg".to_string(),
+        image: base64::engine::general_purpose::STANDARD.encode(bytes),
+    }))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn capture_frontmost() -> Result<Option<WindowCapture>, String> {
+    Err("Window capture is only available on macOS".to_string())
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Captures the frontmost app's front window, or returns `None` when there is
+/// no window to capture or the app is on the exclusion list.
+#[tauri::command]
+pub async fn capture_frontmost_window(
+    core: State<'_, AppCore>,
+) -> Result<Option<WindowCapture>, String> {
+    if active_app::is_frontmost_app_excluded(&core) {
+        return Ok(None);
+    }
+    capture_frontmost()
+}
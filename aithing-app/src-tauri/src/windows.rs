@@ -0,0 +1,97 @@
+//! Secondary windows
+//!
+//! The main overlay is a single non-activating panel. Everything else the app
+//! opens (detached conversations) is a standard window tracked in a registry
+//! here, keyed by what it shows so the same conversation is never opened twice.
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+use crate::app_core::AppCore;
+
+const CONVERSATION_WINDOW_PREFIX: &str = "conversation-";
+
+// =============================================================================
+// STATE
+// =============================================================================
+
+#[derive(Default)]
+pub struct WindowRegistry {
+    /// Conversation ID -> window label.
+    detached: RwLock<HashMap<String, String>>,
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Conversation IDs end up in a window label and a URL query, so only the
+/// characters the frontend's UUIDs use are accepted.
+fn is_valid_conversation_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// =============================================================================
+// TAURI COMMANDS
+// =============================================================================
+
+/// Opens a conversation in its own regular (activating, resizable) window so it
+/// can stay open beside the overlay. Focuses the existing window if the
+/// conversation is already detached. Returns the window label.
+#[tauri::command]
+pub fn detach_conversation(
+    app: AppHandle,
+    core: State<'_, AppCore>,
+    id: String,
+    title: Option<String>,
+) -> Result<String, String> {
+    if !is_valid_conversation_id(&id) {
+        return Err(format!("Invalid conversation ID: {}", id));
+    }
+    let label = format!("{}{}", CONVERSATION_WINDOW_PREFIX, id);
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus conversation window: {}", e))?;
+        return Ok(label);
+    }
+
+    let url = format!("index.html?conversation={}", id);
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(title.unwrap_or_else(|| "AIThing".to_string()))
+        .inner_size(660.0, 600.0)
+        .min_inner_size(400.0, 400.0)
+        .resizable(true)
+        .decorations(true)
+        .focused(true)
+        .build()
+        .map_err(|e| format!("Failed to open conversation window: {}", e))?;
+
+    {
+        let app = app.clone();
+        let conversation_id = id.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                app.state::<AppCore>()
+                    .windows
+                    .detached
+                    .write()
+                    .remove(&conversation_id);
+            }
+        });
+    }
+
+    core.windows.detached.write().insert(id, label.clone());
+    Ok(label)
+}
+
+/// Returns the IDs of conversations currently open in their own window.
+#[tauri::command]
+pub fn list_detached_conversations(core: State<'_, AppCore>) -> Vec<String> {
+    core.windows.detached.read().keys().cloned().collect()
+}
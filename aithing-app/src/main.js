@@ -4,10 +4,10 @@
 // =============================================================================
 // TAURI API IMPORTS
 // =============================================================================
-const { invoke } = window.__TAURI__.core;
+const { invoke, Channel } = window.__TAURI__.core;
 const { listen } = window.__TAURI__.event;
 const { getCurrentWindow } = window.__TAURI__.window;
-const { exit } = window.__TAURI__.process;
+const dialog = window.__TAURI__.dialog;
 
 // =============================================================================
 // DOM ELEMENTS
@@ -39,10 +39,13 @@ const elements = {
     inputContainer: document.getElementById('inputContainer'),
     messageInput: document.getElementById('messageInput'),
     animatedBorder: document.getElementById('animatedBorder'),
+    chordHint: document.getElementById('chordHint'),
     contextBar: document.getElementById('contextBar'),
     contextItems: document.getElementById('contextItems'),
     textSelectionBtn: document.getElementById('textSelectionBtn'),
     viewToolsBtn: document.getElementById('viewToolsBtn'),
+    keepAwakeBtn: document.getElementById('keepAwakeBtn'),
+    quietHoursIndicator: document.getElementById('quietHoursIndicator'),
 
     // Settings View
     settingsCloseBtn: document.getElementById('settingsCloseBtn'),
@@ -59,12 +62,27 @@ const elements = {
     anthropicModels: document.getElementById('anthropicModels'),
     openaiModels: document.getElementById('openaiModels'),
     googleModels: document.getElementById('googleModels'),
+    mockModels: document.getElementById('mockModels'),
     agentsList: document.getElementById('agentsList'),
     addAgentBtn: document.getElementById('addAgentBtn'),
     showInScreenshot: document.getElementById('showInScreenshot'),
     useCapturedScreenshots: document.getElementById('useCapturedScreenshots'),
+    includeActiveWindow: document.getElementById('includeActiveWindow'),
+    excludeFromMissionControl: document.getElementById('excludeFromMissionControl'),
     openAtLogin: document.getElementById('openAtLogin'),
     shortcutsEnabled: document.getElementById('shortcutsEnabled'),
+    contactsLookupEnabled: document.getElementById('contactsLookupEnabled'),
+    currencyRatesEnabled: document.getElementById('currencyRatesEnabled'),
+    accessibilityAnnouncements: document.getElementById('accessibilityAnnouncements'),
+    configPassphrase: document.getElementById('configPassphrase'),
+    exportConfigBtn: document.getElementById('exportConfigBtn'),
+    importConfigBtn: document.getElementById('importConfigBtn'),
+    diagnosticsBtn: document.getElementById('diagnosticsBtn'),
+    diagnosticsReport: document.getElementById('diagnosticsReport'),
+    diagnosticsText: document.getElementById('diagnosticsText'),
+    copyDiagnosticsBtn: document.getElementById('copyDiagnosticsBtn'),
+    relaunchBtn: document.getElementById('relaunchBtn'),
+    factoryResetBtn: document.getElementById('factoryResetBtn'),
     quitBtn: document.getElementById('quitBtn'),
 
     // Logo
@@ -90,8 +108,18 @@ const state = {
     modelContext: [],
     selectionEnabled: false,
 
+    // Messages were dropped to save memory while hidden
+    messagesTrimmed: false,
+
+    // Why the display is being kept awake
+    sleepPreventionReasons: [],
+
     // Settings state
     showSettings: false,
+    backendSettings: {},
+    policy: null,
+    // Provider -> where its key comes from when none is saved here
+    credentialSources: {},
     selectedTab: 'account',
     selectedModel: 'claude-sonnet-4-20250514',
     apiKeys: {
@@ -103,8 +131,13 @@ const state = {
     preferences: {
         showInScreenshot: false,
         useCapturedScreenshots: false,
+        includeActiveWindow: false,
+        excludeFromMissionControl: false,
         openAtLogin: false,
-        shortcutsEnabled: true
+        shortcutsEnabled: true,
+        contactsLookupEnabled: false,
+        currencyRatesEnabled: false,
+        accessibilityAnnouncements: false
     }
 };
 
@@ -118,6 +151,7 @@ const availableModels = [
     { id: 'o1', name: 'O1', provider: 'openai' },
     { id: 'gemini-2.0-flash', name: 'Gemini 2.0 Flash', provider: 'google' },
     { id: 'gemini-1.5-pro', name: 'Gemini 1.5 Pro', provider: 'google' },
+    { id: 'mock', name: 'Mock (no API calls)', provider: 'mock' },
 ];
 
 // =============================================================================
@@ -235,6 +269,41 @@ function updateGreeting() {
     elements.subheading.textContent = timeBasedSubheading();
 }
 
+function applyTheme(theme) {
+    const root = document.documentElement.style;
+    Object.entries(theme.colors).forEach(([name, value]) => {
+        root.setProperty(`--${name}`, value);
+    });
+    root.setProperty('--corner-radius', `${theme.corner_radius}px`);
+    root.setProperty('--blur-radius', `${theme.blur_radius}px`);
+    document.body.style.fontFamily = theme.font_family;
+    document.body.style.fontSize = `${theme.font_size}px`;
+}
+
+function applyAccessibilityPrefs(prefs) {
+    const root = document.documentElement.classList;
+    root.toggle('reduce-motion', prefs.reduce_motion);
+    root.toggle('reduce-transparency', prefs.reduce_transparency);
+    root.toggle('increase-contrast', prefs.increase_contrast);
+    document.documentElement.style.setProperty('--text-scale', prefs.text_scale);
+}
+
+async function loadAccessibilityPrefs() {
+    try {
+        applyAccessibilityPrefs(await invoke('get_accessibility_prefs'));
+    } catch (error) {
+        console.error('Failed to load accessibility preferences:', error);
+    }
+}
+
+async function loadTheme() {
+    try {
+        applyTheme(await invoke('get_theme'));
+    } catch (error) {
+        console.error('Failed to load theme:', error);
+    }
+}
+
 function toggleSidebar() {
     state.sidebarExpanded = !state.sidebarExpanded;
     elements.sidebar.classList.toggle('collapsed', !state.sidebarExpanded);
@@ -312,6 +381,14 @@ function updateMessages() {
                     contentDiv.className = 'message-content';
                     contentDiv.innerHTML = parseMarkdown(payload.text);
                     messageDiv.appendChild(contentDiv);
+
+                    // Swap in the backend rendering with highlighted code once ready
+                    invoke('render_markdown', { text: payload.text, theme: 'dark' })
+                        .then(html => {
+                            contentDiv.innerHTML = html;
+                            fitToContent();
+                        })
+                        .catch(error => console.error('Failed to render markdown:', error));
                 } else if (payload.type === 'imageBase64') {
                     const imgDiv = document.createElement('div');
                     imgDiv.className = 'image-bubble';
@@ -331,6 +408,7 @@ function updateMessages() {
 
     // Scroll to bottom
     elements.chatArea.scrollTop = elements.chatArea.scrollHeight;
+    fitToContent();
 }
 
 function setThinking(thinking, text = 'Responding...') {
@@ -357,17 +435,40 @@ function setThinking(thinking, text = 'Responding...') {
     }
 }
 
+function isProviderAllowed(provider) {
+    const allowed = state.policy?.allowed_providers ?? [];
+    return allowed.length === 0 || allowed.includes(provider);
+}
+
+// Select `modelId`, or the first allowed model when policy locks out its
+// provider, as for a model restored from storage, an import, or a profile
+function selectModel(modelId) {
+    state.selectedModel = modelId;
+    if (!isProviderAllowed(getProviderForModel(modelId))) {
+        const allowed = availableModels.find(model => isProviderAllowed(model.provider));
+        if (allowed) {
+            state.selectedModel = allowed.id;
+        }
+    }
+}
+
 function updateModelsList() {
     const providers = {
         anthropic: elements.anthropicModels,
         openai: elements.openaiModels,
-        google: elements.googleModels
+        google: elements.googleModels,
+        mock: elements.mockModels
     };
 
     Object.keys(providers).forEach(provider => {
         const container = providers[provider];
         container.innerHTML = '';
 
+        if (!isProviderAllowed(provider)) {
+            container.innerHTML = '<div class="no-agents">Disabled by your organization</div>';
+            return;
+        }
+
         availableModels
             .filter(model => model.provider === provider)
             .forEach(model => {
@@ -375,8 +476,8 @@ function updateModelsList() {
                 item.className = 'model-item';
                 item.innerHTML = `
                     <div class="model-info">
-                        <span class="model-name">${model.name}</span>
-                        <span class="model-id">${model.id}</span>
+                        <span class="model-name">${escapeHtml(model.name)}</span>
+                        <span class="model-id">${escapeHtml(model.id)}</span>
                     </div>
                     <label class="toggle-switch">
                         <input type="checkbox" ${state.selectedModel === model.id ? 'checked' : ''}>
@@ -398,8 +499,13 @@ function updateModelsList() {
 function updatePreferences() {
     elements.showInScreenshot.checked = state.preferences.showInScreenshot;
     elements.useCapturedScreenshots.checked = state.preferences.useCapturedScreenshots;
+    elements.includeActiveWindow.checked = state.preferences.includeActiveWindow;
+    elements.excludeFromMissionControl.checked = state.preferences.excludeFromMissionControl;
     elements.openAtLogin.checked = state.preferences.openAtLogin;
     elements.shortcutsEnabled.checked = state.preferences.shortcutsEnabled;
+    elements.contactsLookupEnabled.checked = state.preferences.contactsLookupEnabled;
+    elements.currencyRatesEnabled.checked = state.preferences.currencyRatesEnabled;
+    elements.accessibilityAnnouncements.checked = state.preferences.accessibilityAnnouncements;
 }
 
 function switchSettingsTab(tabName) {
@@ -471,8 +577,19 @@ async function sendMessage() {
     setThinking(true);
 
     try {
-        // Call AI provider (placeholder - implement actual API calls)
-        const response = await callAIProvider(query);
+        // Answer locally when possible, otherwise call the AI provider
+        let response = await quickAnswer(query);
+        if (response === null) {
+            const capabilities = await getModelCapabilities(state.selectedModel);
+            const canReadImages = capabilities?.vision ?? true;
+            if (!canReadImages && state.modelContext.some(item => item.type === 'image')) {
+                throw new Error(`${state.selectedModel} can't read images. Remove the image attachments or pick a vision model.`);
+            }
+            if (canReadImages) {
+                await attachActiveWindow();
+            }
+            response = await callAIProvider(query);
+        }
 
         // Add assistant message
         state.chatHistory.push({
@@ -480,6 +597,7 @@ async function sendMessage() {
             role: 'assistant',
             payloads: [{ type: 'text', text: response }]
         });
+        announceResponseFinished(response);
 
         // Save history
         await saveCurrentHistory();
@@ -497,15 +615,198 @@ async function sendMessage() {
     }
 }
 
+function announceResponseFinished(response) {
+    // The backend only speaks when announcements are enabled
+    invoke('announce_response_finished', { preview: response })
+        .catch(error => console.error('Failed to announce response:', error));
+}
+
+async function getModelCapabilities(model) {
+    try {
+        return await invoke('get_model_capabilities', { model });
+    } catch (error) {
+        console.error('Failed to get model capabilities:', error);
+        return null;
+    }
+}
+
+// No context may be taken while an app on the exclusion list is frontmost.
+// Fails closed: an unknown app is treated as excluded.
+async function isActiveAppExcluded() {
+    try {
+        const activeApp = await invoke('get_active_app');
+        return activeApp.excluded;
+    } catch (error) {
+        console.error('Failed to check active app:', error);
+        return true;
+    }
+}
+
+async function attachActiveWindow() {
+    if (!state.preferences.includeActiveWindow) return;
+
+    try {
+        const capture = await invoke('capture_frontmost_window');
+        // Each message gets the current window, not every earlier one
+        state.modelContext = state.modelContext.filter(item => !item.auto);
+        if (capture) {
+            state.modelContext.push({ type: 'image', auto: true, ...capture });
+        }
+        updateContextItems();
+    } catch (error) {
+        console.error('Failed to capture active window:', error);
+    }
+}
+
+// Attach a window the backend captured for a quick-capture shortcut
+function attachCapturedWindow(capture) {
+    if (capture) {
+        state.modelContext.push({ type: 'image', ...capture });
+        updateContextItems();
+    }
+    elements.messageInput.focus();
+}
+
+// Send a quick action's prompt as if it had been typed
+async function runQuickAction(prompt) {
+    elements.messageInput.value = prompt;
+    await sendMessage();
+}
+
+// List the keys that complete a pending leader-key chord
+function showChordHint(hint) {
+    elements.chordHint.replaceChildren(...hint.bindings.map(binding => {
+        const item = document.createElement('span');
+        const key = document.createElement('kbd');
+        key.textContent = binding.key;
+        item.append(key, binding.label);
+        return item;
+    }));
+    elements.chordHint.classList.remove('hidden');
+}
+
+function hideChordHint() {
+    elements.chordHint.classList.add('hidden');
+    elements.chordHint.replaceChildren();
+}
+
+// Show what's keeping the display awake, if anything
+function showSleepPrevention(reasons) {
+    state.sleepPreventionReasons = reasons;
+    elements.keepAwakeBtn.classList.toggle('hidden', reasons.length === 0);
+    elements.keepAwakeBtn.title = `Keeping the display awake: ${reasons.join(', ')}. Click to allow sleep.`;
+}
+
+function showQuietHours(quiet) {
+    elements.quietHoursIndicator.classList.toggle('hidden', !quiet);
+}
+
+async function allowSleep() {
+    for (const reason of state.sleepPreventionReasons) {
+        try {
+            await invoke('set_sleep_prevention', { reason, enabled: false });
+        } catch (error) {
+            console.error('Failed to allow display sleep:', error);
+        }
+    }
+}
+
+// Drop rendered messages while hidden; they're redrawn from state on show
+function trimMessages() {
+    if (state.isThinking) return;
+    elements.messages.replaceChildren();
+    state.messagesTrimmed = true;
+}
+
+// Hand in-memory state to the backend before the page is unloaded
+async function unloadWebview() {
+    if (state.isThinking) return;
+    try {
+        await invoke('unload_webview', {
+            snapshot: {
+                currentTabId: state.currentTabId,
+                chatHistory: state.chatHistory,
+                modelContext: state.modelContext,
+                title: elements.titleInput.value,
+                draft: elements.messageInput.value
+            }
+        });
+    } catch (error) {
+        console.error('Failed to unload panel:', error);
+    }
+}
+
+// Pick up the state handed over before the last unload, if any. Returns
+// whether there was one.
+async function restoreWebviewSnapshot() {
+    if (getCurrentWindow().label !== 'main') return false;
+    const snapshot = await invoke('take_webview_snapshot');
+    if (!snapshot) return false;
+
+    state.currentTabId = snapshot.currentTabId;
+    state.chatHistory = snapshot.chatHistory;
+    state.modelContext = snapshot.modelContext;
+    elements.titleInput.value = snapshot.title;
+    elements.messageInput.value = snapshot.draft;
+    updateMessages();
+    updateContextItems();
+    return true;
+}
+
+// Focus the input and report the activation as complete
+async function focusInput() {
+    elements.messageInput.focus();
+    try {
+        await invoke('mark_input_focused');
+    } catch (error) {
+        console.error('Failed to report activation:', error);
+    }
+}
+
+async function quickAnswer(query) {
+    const define = query.match(/^define:?\s+(.+)$/i);
+    if (define) {
+        try {
+            const entry = await invoke('lookup_definition', { word: define[1] });
+            if (entry) {
+                return `**${entry.word}**\n\n${entry.definition}`;
+            }
+        } catch (error) {
+            console.error('Failed to look up definition:', error);
+        }
+        return null;
+    }
+
+    try {
+        const result = await invoke('evaluate_expression', { text: query });
+        if (result) {
+            return `${result.expression} = **${result.formatted}**`;
+        }
+    } catch (error) {
+        console.error('Failed to evaluate expression:', error);
+    }
+    return null;
+}
+
 async function callAIProvider(query) {
     // This is a placeholder - in the real implementation, you would:
-    // 1. Get the selected model and API key
+    // 1. Get the selected model and check it has credentials
     // 2. Make the API call to the appropriate provider
     // 3. Stream the response
 
-    const apiKey = state.apiKeys[getProviderForModel(state.selectedModel)];
+    const provider = getProviderForModel(state.selectedModel);
+    if (!isProviderAllowed(provider)) {
+        throw new Error(`${state.selectedModel} is disabled by your organization. Pick another model in Settings > Models.`);
+    }
+    if (provider === 'mock') {
+        return streamMockResponse(query);
+    }
+
+    // The key itself never comes from here; the backend resolves it
+    // (saved, environment, or .env file) through secrets::resolve
+    const hasCredentials = Boolean(state.apiKeys[provider] || state.credentialSources[provider]);
 
-    if (!apiKey) {
+    if (!hasCredentials) {
         return "Please add an API key in Settings > Models to use this feature.";
     }
 
@@ -513,6 +814,43 @@ async function callAIProvider(query) {
     return `I received your message: "${query}"\n\nThis is a placeholder response. Connect me to your preferred AI provider (Anthropic, OpenAI, or Google) through the API key settings to get real responses.`;
 }
 
+function streamMockResponse(query) {
+    return new Promise((resolve, reject) => {
+        let text = '';
+        const onEvent = new Channel();
+        onEvent.onmessage = (message) => {
+            if (message.event === 'delta') {
+                text += message.data.text;
+            } else if (message.event === 'done') {
+                resolve(text);
+            }
+        };
+        invoke('mock_complete', { prompt: query, onEvent })
+            .catch(error => reject(new Error(error)));
+    });
+}
+
+async function refreshProviderModels(provider, refresh = false) {
+    // Without a saved key the backend falls back to the environment's
+    const key = state.apiKeys[provider] || null;
+    if (!key && !state.credentialSources[provider]) return;
+    if (!isProviderAllowed(provider)) return;
+
+    try {
+        const models = await invoke('list_models', { provider, key, refresh });
+        // Replace the built-in entries for this provider with the live catalog
+        for (let i = availableModels.length - 1; i >= 0; i--) {
+            if (availableModels[i].provider === provider) {
+                availableModels.splice(i, 1);
+            }
+        }
+        availableModels.push(...models.map(({ id, name }) => ({ id, name, provider })));
+        updateModelsList();
+    } catch (error) {
+        console.error(`Failed to load ${provider} models:`, error);
+    }
+}
+
 function getProviderForModel(modelId) {
     const model = availableModels.find(m => m.id === modelId);
     return model ? model.provider : 'anthropic';
@@ -522,18 +860,38 @@ function getProviderForModel(modelId) {
 // STORAGE FUNCTIONS
 // =============================================================================
 
+function applyBackendSettings(settings) {
+    state.backendSettings = settings;
+    state.preferences.showInScreenshot = settings.show_in_screenshot;
+    state.preferences.openAtLogin = settings.open_at_login;
+    state.preferences.excludeFromMissionControl = settings.exclude_from_mission_control;
+    state.preferences.includeActiveWindow = settings.include_active_window;
+    state.preferences.shortcutsEnabled = settings.shortcuts_enabled;
+    state.preferences.contactsLookupEnabled = settings.contacts_lookup_enabled;
+    state.preferences.currencyRatesEnabled = settings.currency_rates_enabled;
+    state.preferences.accessibilityAnnouncements = settings.accessibility_announcements;
+}
+
 async function loadSettings() {
     try {
         const settings = await invoke('get_settings');
         if (settings) {
-            state.preferences.showInScreenshot = settings.show_in_screenshot;
-            state.preferences.openAtLogin = settings.open_at_login;
-            state.preferences.shortcutsEnabled = settings.shortcuts_enabled;
+            applyBackendSettings(settings);
+            state.policy = await invoke('get_policy');
         }
     } catch (error) {
         console.error('Failed to load settings:', error);
     }
 
+    try {
+        const sources = await invoke('get_credential_sources');
+        state.credentialSources = Object.fromEntries(sources
+            .filter(status => status.source)
+            .map(status => [status.provider, status.source]));
+    } catch (error) {
+        console.error('Failed to load credential sources:', error);
+    }
+
     // Load from localStorage as fallback
     const saved = localStorage.getItem('aithing_state');
     if (saved) {
@@ -546,19 +904,27 @@ async function loadSettings() {
             console.error('Failed to parse saved state:', e);
         }
     }
+    selectModel(state.selectedModel);
 
     updatePreferences();
     updateModelsList();
     updateChatList();
+    Object.keys(state.apiKeys).forEach(provider => refreshProviderModels(provider));
 }
 
 async function saveSettings() {
     try {
         await invoke('set_settings', {
             settings: {
+                // Keep backend-only settings the preferences UI doesn't expose
+                ...state.backendSettings,
                 show_in_screenshot: state.preferences.showInScreenshot,
                 open_at_login: state.preferences.openAtLogin,
-                shortcuts_enabled: state.preferences.shortcutsEnabled
+                include_active_window: state.preferences.includeActiveWindow,
+                exclude_from_mission_control: state.preferences.excludeFromMissionControl,
+                shortcuts_enabled: state.preferences.shortcutsEnabled,
+                contacts_lookup_enabled: state.preferences.contactsLookupEnabled,
+                currency_rates_enabled: state.preferences.currencyRatesEnabled
             }
         });
     } catch (error) {
@@ -569,7 +935,7 @@ async function saveSettings() {
     localStorage.setItem('aithing_state', JSON.stringify({
         selectedModel: state.selectedModel,
         apiKeys: state.apiKeys,
-        histories: state.histories
+        histories: state.policy?.disable_history ? [] : state.histories
     }));
 }
 
@@ -609,9 +975,73 @@ async function saveCurrentHistory() {
 }
 
 async function saveHistories() {
+    if (state.policy?.disable_history) {
+        localStorage.removeItem('aithing_histories');
+        return;
+    }
     localStorage.setItem('aithing_histories', JSON.stringify(state.histories));
 }
 
+const CONFIG_FILE_FILTERS = [{ name: 'AIThing configuration', extensions: ['json'] }];
+
+// Saved API keys go along, encrypted, only when a passphrase is entered
+async function exportConfig() {
+    const path = await dialog.save({ defaultPath: 'aithing-config.json', filters: CONFIG_FILE_FILTERS });
+    if (!path) return;
+
+    const passphrase = elements.configPassphrase.value.trim();
+    const apiKeys = Object.fromEntries(
+        Object.entries(state.apiKeys).filter(([, key]) => key.trim())
+    );
+    const withKeys = passphrase && Object.keys(apiKeys).length > 0;
+
+    try {
+        await invoke('export_config', {
+            path,
+            frontend: { selectedModel: state.selectedModel },
+            apiKeys: withKeys ? apiKeys : null,
+            passphrase: withKeys ? passphrase : null
+        });
+        elements.configPassphrase.value = '';
+    } catch (error) {
+        console.error('Failed to export configuration:', error);
+    }
+}
+
+async function importConfig() {
+    const path = await dialog.open({ multiple: false, filters: CONFIG_FILE_FILTERS });
+    if (!path) return;
+
+    try {
+        const passphrase = elements.configPassphrase.value.trim() || null;
+        const { frontend, api_keys, api_keys_locked } = await invoke('import_config', { path, passphrase });
+        await loadSettings();
+        if (frontend.selectedModel) {
+            selectModel(frontend.selectedModel);
+            updateModelsList();
+        }
+        if (api_keys) {
+            Object.entries(api_keys)
+                .filter(([provider]) => provider in state.apiKeys)
+                .forEach(([provider, key]) => {
+                    state.apiKeys[provider] = key;
+                    elements[`${provider}ApiKey`].value = key;
+                    refreshProviderModels(provider);
+                });
+        }
+        await saveSettings();
+        await loadTheme();
+        elements.configPassphrase.value = '';
+        if (api_keys_locked) {
+            // The rest is imported; import again with the passphrase for the keys
+            elements.configPassphrase.placeholder = 'Passphrase for API keys';
+            elements.configPassphrase.focus();
+        }
+    } catch (error) {
+        console.error('Failed to import configuration:', error);
+    }
+}
+
 function loadHistories() {
     const saved = localStorage.getItem('aithing_histories');
     if (saved) {
@@ -633,19 +1063,100 @@ async function closeWindow() {
     await window.hide();
 }
 
-async function toggleVisibility() {
+// Quit without losing the answer being streamed, unless the user says so
+async function quitApp() {
+    let force = false;
+    if (state.isThinking) {
+        try {
+            const wait = await dialog.ask('A response is still coming in. Wait for it before quitting?', {
+                title: 'Quit AIThing',
+                okLabel: 'Wait',
+                cancelLabel: 'Quit Now'
+            });
+            force = !wait;
+        } catch (error) {
+            // Still quit, waiting for the response as if the user chose to
+            console.error('Failed to ask about quitting:', error);
+        }
+    }
+
     try {
-        await invoke('toggle_visibility');
+        if (state.chatHistory.length > 0) {
+            await saveCurrentHistory();
+        }
+        await invoke('quit_app', { force });
     } catch (error) {
-        console.error('Failed to toggle visibility:', error);
+        console.error('Failed to quit app:', error);
     }
 }
 
-async function quitApp() {
+// Grow the panel with its messages. Renders arrive with every streamed
+// chunk, so resizing is throttled to a few times a second.
+const FIT_TO_CONTENT_INTERVAL_MS = 150;
+let fitToContentTimer = null;
+
+function fitToContent() {
+    if (fitToContentTimer) return;
+    fitToContentTimer = setTimeout(async () => {
+        fitToContentTimer = null;
+        const overflow = elements.chatArea.scrollHeight - elements.chatArea.clientHeight;
+        if (overflow < 1) return;
+        try {
+            await invoke('resize_to_content', { height: window.innerHeight + overflow });
+            elements.chatArea.scrollTop = elements.chatArea.scrollHeight;
+        } catch (error) {
+            console.error('Failed to resize to content:', error);
+        }
+    }, FIT_TO_CONTENT_INTERVAL_MS);
+}
+
+const DIAGNOSTIC_MARKS = { pass: '✓', warn: '!', fail: '✗', skipped: '–' };
+
+// Run the support checks and show the report under the button, where it
+// can be copied for support
+async function runDiagnostics() {
+    elements.diagnosticsBtn.disabled = true;
     try {
-        await exit(0);
+        const report = await invoke('run_diagnostics');
+        const lines = report.checks.map(check =>
+            `${DIAGNOSTIC_MARKS[check.status]} ${check.label}: ${check.detail}`
+        );
+        elements.diagnosticsText.textContent = `AIThing ${report.version} (${report.os})\n${report.generated_at}\n\n${lines.join('\n')}`;
+        elements.diagnosticsReport.classList.toggle('failed', report.status === 'fail');
+        elements.diagnosticsReport.classList.remove('hidden');
     } catch (error) {
-        console.error('Failed to quit app:', error);
+        console.error('Failed to run diagnostics:', error);
+    } finally {
+        elements.diagnosticsBtn.disabled = false;
+    }
+}
+
+async function copyDiagnostics() {
+    try {
+        // Markdown copies the text as is
+        await invoke('copy_response', { text: elements.diagnosticsText.textContent, format: 'markdown' });
+    } catch (error) {
+        console.error('Failed to copy diagnostics:', error);
+    }
+}
+
+async function relaunchApp() {
+    try {
+        if (state.chatHistory.length > 0) {
+            await saveCurrentHistory();
+        }
+        await invoke('relaunch_app');
+    } catch (error) {
+        console.error('Failed to relaunch app:', error);
+    }
+}
+
+// Erase everything and start over; the backend asks the user to confirm
+async function factoryReset() {
+    try {
+        await invoke('factory_reset');
+    } catch (error) {
+        console.error('Failed to reset app:', error);
     }
 }
 
@@ -668,6 +1179,18 @@ function setupEventListeners() {
         }
     });
 
+    // Open response links in the default browser instead of the webview
+    elements.messages.addEventListener('click', async (e) => {
+        const link = e.target.closest('a[href]');
+        if (!link) return;
+        e.preventDefault();
+        try {
+            await invoke('open_url_in_default_browser', { url: link.href });
+        } catch (error) {
+            console.error('Failed to open link:', error);
+        }
+    });
+
     // Auto-resize textarea
     elements.messageInput.addEventListener('input', function() {
         this.style.height = 'auto';
@@ -691,6 +1214,7 @@ function setupEventListeners() {
         state.selectionEnabled = !state.selectionEnabled;
         elements.textSelectionBtn.classList.toggle('active', state.selectionEnabled);
     });
+    elements.keepAwakeBtn.addEventListener('click', allowSleep);
 
     // Settings View
     elements.settingsCloseBtn.addEventListener('click', showIntelligenceView);
@@ -706,29 +1230,26 @@ function setupEventListeners() {
     elements.anthropicApiKey.addEventListener('change', () => {
         state.apiKeys.anthropic = elements.anthropicApiKey.value;
         saveSettings();
+        refreshProviderModels('anthropic', true);
     });
 
     elements.openaiApiKey.addEventListener('change', () => {
         state.apiKeys.openai = elements.openaiApiKey.value;
         saveSettings();
+        refreshProviderModels('openai', true);
     });
 
     elements.googleApiKey.addEventListener('change', () => {
         state.apiKeys.google = elements.googleApiKey.value;
         saveSettings();
+        refreshProviderModels('google', true);
     });
 
     // Preferences
-    elements.showInScreenshot.addEventListener('change', async () => {
+    // The backend applies screenshot protection and shortcuts when they change
+    elements.showInScreenshot.addEventListener('change', () => {
         state.preferences.showInScreenshot = elements.showInScreenshot.checked;
-        await saveSettings();
-        try {
-            await invoke('set_screenshot_protection', {
-                enabled: !state.preferences.showInScreenshot
-            });
-        } catch (e) {
-            console.error('Failed to update screenshot protection:', e);
-        }
+        saveSettings();
     });
 
     elements.useCapturedScreenshots.addEventListener('change', () => {
@@ -736,23 +1257,52 @@ function setupEventListeners() {
         saveSettings();
     });
 
+    elements.includeActiveWindow.addEventListener('change', () => {
+        state.preferences.includeActiveWindow = elements.includeActiveWindow.checked;
+        saveSettings();
+    });
+
+    elements.excludeFromMissionControl.addEventListener('change', () => {
+        state.preferences.excludeFromMissionControl = elements.excludeFromMissionControl.checked;
+        saveSettings();
+    });
+
     elements.openAtLogin.addEventListener('change', () => {
         state.preferences.openAtLogin = elements.openAtLogin.checked;
         saveSettings();
     });
 
-    elements.shortcutsEnabled.addEventListener('change', async () => {
+    elements.shortcutsEnabled.addEventListener('change', () => {
         state.preferences.shortcutsEnabled = elements.shortcutsEnabled.checked;
-        await saveSettings();
+        saveSettings();
+    });
+
+    elements.contactsLookupEnabled.addEventListener('change', () => {
+        state.preferences.contactsLookupEnabled = elements.contactsLookupEnabled.checked;
+        saveSettings();
+    });
+
+    elements.currencyRatesEnabled.addEventListener('change', () => {
+        state.preferences.currencyRatesEnabled = elements.currencyRatesEnabled.checked;
+        saveSettings();
+    });
+
+    elements.accessibilityAnnouncements.addEventListener('change', async () => {
         try {
-            await invoke('set_shortcuts_enabled', {
-                enabled: state.preferences.shortcutsEnabled
+            await invoke('set_accessibility_announcements', {
+                enabled: elements.accessibilityAnnouncements.checked
             });
-        } catch (e) {
-            console.error('Failed to update shortcuts:', e);
+        } catch (error) {
+            console.error('Failed to update announcements:', error);
         }
     });
 
+    elements.exportConfigBtn.addEventListener('click', exportConfig);
+    elements.importConfigBtn.addEventListener('click', importConfig);
+    elements.diagnosticsBtn.addEventListener('click', runDiagnostics);
+    elements.copyDiagnosticsBtn.addEventListener('click', copyDiagnostics);
+    elements.relaunchBtn.addEventListener('click', relaunchApp);
+    elements.factoryResetBtn.addEventListener('click', factoryReset);
     elements.quitBtn.addEventListener('click', quitApp);
 
     // Drag and drop
@@ -775,7 +1325,12 @@ function setupDragAndDrop() {
         e.preventDefault();
         dropZone.classList.remove('dropping');
 
+        // Read the files before awaiting; the drop's data goes away after
         const files = Array.from(e.dataTransfer.files);
+        if (await isActiveAppExcluded()) {
+            console.warn('Ignoring files dropped while an excluded app is frontmost');
+            return;
+        }
 
         for (const file of files) {
             if (file.type.startsWith('image/')) {
@@ -854,11 +1409,70 @@ function updateContextItems() {
 }
 
 async function setupTauriListeners() {
-    // Listen for shortcut triggers
-    await listen('shortcut-triggered', (event) => {
-        const action = event.payload;
-        if (action === 'toggle-visibility') {
-            toggleVisibility();
+    // Panel shown by a shortcut
+    await listen('panel-shown', async () => {
+        if (state.messagesTrimmed) {
+            state.messagesTrimmed = false;
+            updateMessages();
+        }
+        await focusInput();
+    });
+
+    // Hidden for a while: release memory, or unload the page entirely
+    await listen('memory-trim', () => {
+        trimMessages();
+    });
+    await listen('webview-unload', () => {
+        unloadWebview();
+    });
+
+    // Shortcut actions run in the backend; these carry their results
+    await listen('window-captured', (event) => {
+        attachCapturedWindow(event.payload);
+    });
+    await listen('quick-action', (event) => {
+        runQuickAction(event.payload);
+    });
+
+    // Leader shortcut pressed: show the second keys until the chord ends
+    await listen('chord-started', (event) => {
+        showChordHint(event.payload);
+    });
+    await listen('chord-ended', () => {
+        hideChordHint();
+    });
+
+    await listen('sleep-prevention-changed', (event) => {
+        showSleepPrevention(event.payload);
+    });
+    await listen('quiet-hours-changed', (event) => {
+        showQuietHours(event.payload);
+    });
+
+    // Listen for theme changes
+    await listen('theme-changed', (event) => {
+        applyTheme(event.payload);
+    });
+
+    // Reduce Motion / Reduce Transparency toggled in System Settings
+    await listen('accessibility-prefs-changed', (event) => {
+        applyAccessibilityPrefs(event.payload);
+    });
+
+    // Settings changed from another window or by editing the store file
+    await listen('settings-changed', (event) => {
+        applyBackendSettings(event.payload);
+        updatePreferences();
+    });
+
+    // A profile switch replaces backend settings; restore the frontend's share
+    await listen('profile-changed', async (event) => {
+        const { frontend = {} } = event.payload;
+        await loadSettings();
+        if (frontend.selectedModel) {
+            selectModel(frontend.selectedModel);
+            updateModelsList();
+            await saveSettings();
         }
     });
 }
@@ -872,7 +1486,36 @@ async function init() {
     loadHistories();
     await loadSettings();
 
+    // Reloaded after being unloaded while hidden. The panel is being shown,
+    // but its panel-shown event fired before this page was listening.
+    let reloaded = false;
+    try {
+        reloaded = await restoreWebviewSnapshot();
+    } catch (error) {
+        console.error('Failed to restore panel state:', error);
+    }
+
+    try {
+        showSleepPrevention(await invoke('get_sleep_prevention'));
+    } catch (error) {
+        console.error('Failed to get sleep prevention:', error);
+    }
+
+    try {
+        showQuietHours(await invoke('is_quiet_hours'));
+    } catch (error) {
+        console.error('Failed to get quiet hours:', error);
+    }
+
+    // Detached conversation windows open straight into their conversation
+    const detachedId = new URLSearchParams(window.location.search).get('conversation');
+    if (detachedId) {
+        switchToChat(detachedId);
+    }
+
     // Update UI
+    await loadTheme();
+    await loadAccessibilityPrefs();
     updateGreeting();
     updateModelsList();
     updateChatList();
@@ -885,6 +1528,14 @@ async function init() {
     elements.anthropicApiKey.value = state.apiKeys.anthropic;
     elements.openaiApiKey.value = state.apiKeys.openai;
     elements.googleApiKey.value = state.apiKeys.google;
+    const sourceLabels = { environment: 'Using key from environment', env_file: 'Using key from .env file' };
+    Object.entries(state.credentialSources).forEach(([provider, source]) => {
+        elements[`${provider}ApiKey`].placeholder = sourceLabels[source];
+    });
+
+    if (reloaded) {
+        await focusInput();
+    }
 
     console.log('AIThing initialized successfully');
 }